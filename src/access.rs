@@ -0,0 +1,365 @@
+use crate::{error::PermconError, filetype::FileType, perm::FilePermission};
+use std::{fmt, str::FromStr};
+
+/// The uid of the superuser.
+pub const ROOT_UID: u32 = 0;
+
+/// The owning user and group of a file, parsed from `uid:gid` (e.g., `1000:100`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Owner {
+    pub uid: u32,
+    pub gid: u32,
+}
+
+/// The identity of a process asking for access, parsed from
+/// `uid:gid[,gid...]` where the first gid is the primary one and the rest
+/// are supplementary (e.g., `1001:1000,27`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Credentials {
+    pub uid: u32,
+    pub gid: u32,
+    pub groups: Vec<u32>,
+}
+
+impl Credentials {
+    /// Returns `true` if `gid` is the primary or a supplementary group.
+    pub fn in_group(&self, gid: u32) -> bool {
+        self.gid == gid || self.groups.contains(&gid)
+    }
+}
+
+/// A set of access rights, parsed from any combination of `r`, `w` and `x`
+/// (e.g., `rx`).
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Access {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl Access {
+    /// Returns `true` if every right of `requested` is in `self`.
+    pub fn allows(&self, requested: &Access) -> bool {
+        (self.read || !requested.read)
+            && (self.write || !requested.write)
+            && (self.execute || !requested.execute)
+    }
+}
+
+impl fmt::Display for Access {
+    /// Formats the access like a symbolic triplet (e.g., `r-x`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let triplet: String = ['r', 'w', 'x']
+            .into_iter()
+            .zip([self.read, self.write, self.execute])
+            .map(|(char, is_set)| if is_set { char } else { '-' })
+            .collect();
+
+        write!(f, "{triplet}")
+    }
+}
+
+/// The permission class that decided an access check.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AccessClass {
+    /// The superuser, who bypasses the permission bits.
+    Root,
+    Owner,
+    Group,
+    Other,
+}
+
+/// The result of [`FilePermission::check_access`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AccessCheck {
+    pub class: AccessClass,
+    pub granted: Access,
+}
+
+impl FilePermission {
+    /// Evaluates which rights `credentials` have on a file owned by `owner`,
+    /// the way the kernel does without ACLs.
+    ///
+    /// Only the first matching class counts: the owner gets the user bits
+    /// even if the group or other bits grant more, and members of the owning
+    /// group get the group bits. Root may always read and write, may always
+    /// search a directory, but may only execute a file if at least one
+    /// execute bit is set.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use permcon::access::{AccessClass, Credentials, Owner};
+    /// use permcon::FilePermission;
+    ///
+    /// let perm = FilePermission::try_from("0705").unwrap();
+    /// let owner = Owner::from_str("1000:1000").unwrap();
+    ///
+    /// // a member of the owning group is denied although others are allowed
+    /// let check = perm.check_access(&owner, &Credentials::from_str("1001:1000,27").unwrap());
+    /// assert_eq!(check.class, AccessClass::Group);
+    /// assert_eq!(check.granted.to_string(), "---");
+    ///
+    /// let check = perm.check_access(&owner, &Credentials::from_str("1002:1002").unwrap());
+    /// assert_eq!(check.class, AccessClass::Other);
+    /// assert_eq!(check.granted.to_string(), "r-x");
+    /// ```
+    pub fn check_access(&self, owner: &Owner, credentials: &Credentials) -> AccessCheck {
+        if credentials.uid == ROOT_UID {
+            let any_execute = self.to_perm_group_array().iter().any(|perm| perm.execute);
+
+            return AccessCheck {
+                class: AccessClass::Root,
+                granted: Access {
+                    read: true,
+                    write: true,
+                    execute: self.filetype == FileType::Directory || any_execute,
+                },
+            };
+        }
+
+        let (class, perm) = if credentials.uid == owner.uid {
+            (AccessClass::Owner, &self.user)
+        } else if credentials.in_group(owner.gid) {
+            (AccessClass::Group, &self.group)
+        } else {
+            (AccessClass::Other, &self.other)
+        };
+
+        AccessCheck {
+            class,
+            granted: Access {
+                read: perm.read,
+                write: perm.write,
+                execute: perm.execute,
+            },
+        }
+    }
+}
+
+impl FromStr for Owner {
+    type Err = PermconError;
+
+    /// Tries to parse `uid:gid`.
+    fn from_str(owner: &str) -> Result<Self, Self::Err> {
+        let (uid, gids) = parse_ids(owner)?;
+
+        if let [gid] = gids[..] {
+            return Ok(Owner { uid, gid });
+        }
+
+        let position = owner.chars().position(|ch| ch == ',').unwrap();
+
+        Err(PermconError::InvalidChar {
+            input: owner.to_string(),
+            position,
+            found: ',',
+            expected: String::from("a single gid"),
+        })
+    }
+}
+
+impl FromStr for Credentials {
+    type Err = PermconError;
+
+    /// Tries to parse `uid:gid[,gid...]`.
+    fn from_str(credentials: &str) -> Result<Self, Self::Err> {
+        let (uid, mut groups) = parse_ids(credentials)?;
+        let gid = groups.remove(0);
+
+        Ok(Credentials { uid, gid, groups })
+    }
+}
+
+impl FromStr for Access {
+    type Err = PermconError;
+
+    /// Tries to parse any combination of `r`, `w` and `x`.
+    fn from_str(access: &str) -> Result<Self, Self::Err> {
+        if access.is_empty() {
+            return Err(PermconError::UnexpectedEnd {
+                input: String::new(),
+                expected: String::from("one of 'r', 'w', 'x'"),
+            });
+        }
+
+        let mut requested = Access::default();
+
+        for (position, ch) in access.chars().enumerate() {
+            match ch {
+                'r' => requested.read = true,
+                'w' => requested.write = true,
+                'x' => requested.execute = true,
+                _ => {
+                    return Err(PermconError::InvalidChar {
+                        input: access.to_string(),
+                        position,
+                        found: ch,
+                        expected: String::from("one of 'r', 'w', 'x'"),
+                    })
+                }
+            }
+        }
+
+        Ok(requested)
+    }
+}
+
+/// Parses `uid:gid[,gid...]` into the uid and the non-empty list of gids.
+fn parse_ids(input: &str) -> Result<(u32, Vec<u32>), PermconError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut position = 0;
+
+    let uid = parse_id(input, &chars, &mut position, "a uid")?;
+
+    match chars.get(position) {
+        Some(':') => position += 1,
+        Some(ch) => {
+            return Err(PermconError::InvalidChar {
+                input: input.to_string(),
+                position,
+                found: *ch,
+                expected: String::from("a digit or ':'"),
+            })
+        }
+        None => {
+            return Err(PermconError::UnexpectedEnd {
+                input: input.to_string(),
+                expected: String::from("':' followed by a gid"),
+            })
+        }
+    }
+
+    let mut gids = vec![parse_id(input, &chars, &mut position, "a gid")?];
+
+    while let Some(ch) = chars.get(position) {
+        if *ch != ',' {
+            return Err(PermconError::InvalidChar {
+                input: input.to_string(),
+                position,
+                found: *ch,
+                expected: String::from("a digit or ','"),
+            });
+        }
+
+        position += 1;
+        gids.push(parse_id(input, &chars, &mut position, "a gid")?);
+    }
+
+    Ok((uid, gids))
+}
+
+/// Parses the number at `position` and moves `position` past it.
+fn parse_id(
+    input: &str,
+    chars: &[char],
+    position: &mut usize,
+    expected: &str,
+) -> Result<u32, PermconError> {
+    let start = *position;
+
+    while chars.get(*position).is_some_and(|ch| ch.is_ascii_digit()) {
+        *position += 1;
+    }
+
+    if start == *position {
+        return Err(match chars.get(start) {
+            Some(ch) => PermconError::InvalidChar {
+                input: input.to_string(),
+                position: start,
+                found: *ch,
+                expected: expected.to_string(),
+            },
+            None => PermconError::UnexpectedEnd {
+                input: input.to_string(),
+                expected: expected.to_string(),
+            },
+        });
+    }
+
+    let digits: String = chars[start..*position].iter().collect();
+
+    digits.parse().map_err(|_| PermconError::InvalidChar {
+        input: input.to_string(),
+        position: start,
+        found: chars[start],
+        expected: format!("{expected} within 0..={}", u32::MAX),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestCase<'a> = (&'a str, u32, u32, &'a [u32], AccessClass, &'a str);
+
+    #[test]
+    fn test_check_access() {
+        let owner = Owner {
+            uid: 1000,
+            gid: 100,
+        };
+
+        // (permission, uid, gid, groups, class, granted)
+        let test_cases: [TestCase; 9] = [
+            ("0754", 1000, 1000, &[], AccessClass::Owner, "rwx"),
+            ("0754", 1001, 100, &[], AccessClass::Group, "r-x"),
+            ("0754", 1001, 1001, &[27, 100], AccessClass::Group, "r-x"),
+            ("0754", 1001, 1001, &[27], AccessClass::Other, "r--"),
+            // the owner is denied although everyone else is allowed
+            ("0077", 1000, 100, &[], AccessClass::Owner, "---"),
+            ("0000", 0, 0, &[], AccessClass::Root, "rw-"),
+            ("0001", 0, 0, &[], AccessClass::Root, "rwx"),
+            ("d---------", 0, 0, &[], AccessClass::Root, "rwx"),
+            ("-rw-rw-rw-", 0, 0, &[], AccessClass::Root, "rw-"),
+        ];
+
+        for (perm, uid, gid, groups, class, granted) in test_cases {
+            let credentials = Credentials {
+                uid,
+                gid,
+                groups: groups.to_vec(),
+            };
+
+            let check = FilePermission::try_from(perm)
+                .unwrap()
+                .check_access(&owner, &credentials);
+
+            assert_eq!(check.class, class, "{perm} as {uid}");
+            assert_eq!(check.granted.to_string(), granted, "{perm} as {uid}");
+        }
+    }
+
+    #[test]
+    fn test_parse_ids() {
+        assert_eq!(
+            Credentials::from_str("1001:1000,27,4"),
+            Ok(Credentials {
+                uid: 1001,
+                gid: 1000,
+                groups: vec![27, 4],
+            })
+        );
+        assert_eq!(Owner::from_str("0:0"), Ok(Owner { uid: 0, gid: 0 }));
+
+        // (input, error position)
+        let test_cases: [(&str, usize); 6] = [
+            ("1000", 4),
+            ("1000:", 5),
+            ("10a0:1", 2),
+            ("1000:1,", 7),
+            ("1000:1;2", 6),
+            (":1", 0),
+        ];
+
+        for (input, position) in test_cases {
+            let error = Credentials::from_str(input).unwrap_err();
+            assert_eq!(error.position(), Some(position), "{input}");
+        }
+
+        assert_eq!(Owner::from_str("1:2,3").unwrap_err().position(), Some(3));
+        assert!(Access::from_str("rz").is_err());
+        assert!(Access::from_str("rx")
+            .unwrap()
+            .allows(&Access::from_str("x").unwrap()));
+    }
+}
@@ -0,0 +1,614 @@
+use crate::{
+    error::PermconError,
+    filetype::FileType,
+    perm::{FilePermission, GroupPermission, SpecialPermission},
+};
+use std::{fmt, str::FromStr};
+
+/// The tag and qualifier of an ACL entry, e.g., the `user:joe` of `user:joe:rwx`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AclTag {
+    /// `user::`, the owner.
+    UserObj,
+    /// `user:name:`, a named user (or uid).
+    User(String),
+    /// `group::`, the owning group.
+    GroupObj,
+    /// `group:name:`, a named group (or gid).
+    Group(String),
+    /// `mask::`, the upper bound of the group class entries.
+    Mask,
+    /// `other::`
+    Other,
+}
+
+impl AclTag {
+    /// Returns `true` for the entries the mask applies to: named users, the
+    /// owning group and named groups.
+    pub fn is_group_class(&self) -> bool {
+        matches!(self, AclTag::User(_) | AclTag::GroupObj | AclTag::Group(_))
+    }
+}
+
+impl fmt::Display for AclTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AclTag::UserObj => write!(f, "user:"),
+            AclTag::User(name) => write!(f, "user:{name}"),
+            AclTag::GroupObj => write!(f, "group:"),
+            AclTag::Group(name) => write!(f, "group:{name}"),
+            AclTag::Mask => write!(f, "mask:"),
+            AclTag::Other => write!(f, "other:"),
+        }
+    }
+}
+
+/// A single ACL entry, e.g., `default:user:joe:rwx`. The `special` flag of
+/// `permission` is never set.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct AclEntry {
+    /// `true` for the `default:` entries new files in a directory inherit.
+    pub default: bool,
+    pub tag: AclTag,
+    pub permission: GroupPermission,
+}
+
+impl fmt::Display for AclEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let prefix = if self.default { "default:" } else { "" };
+        write!(
+            f,
+            "{prefix}{}:{}",
+            self.tag,
+            self.permission.to_symbolic_str(&'x')
+        )
+    }
+}
+
+impl FromStr for AclEntry {
+    type Err = PermconError;
+
+    /// Tries to parse a `getfacl` line or a single `setfacl -m` entry:
+    ///
+    /// ```text
+    /// entry ::= [d[efault]:] tag : [qualifier] : perms
+    /// tag   ::= u[ser] | g[roup] | m[ask] | o[ther]
+    /// perms ::= [rwx-]... | an octal digit
+    /// ```
+    ///
+    /// The qualifier and its colon may be omitted for `mask` and `other`.
+    fn from_str(entry: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = entry.split(':').collect();
+
+        // the char position of every field
+        let positions: Vec<usize> = fields
+            .iter()
+            .scan(0, |position, field| {
+                let start = *position;
+                *position += field.chars().count() + 1;
+                Some(start)
+            })
+            .collect();
+
+        let is_default = matches!(fields[0], "d" | "default") && fields.len() > 2;
+        let start = if is_default { 1 } else { 0 };
+        let (fields, positions) = (&fields[start..], &positions[start..]);
+
+        let invalid_char = |position: usize, expected: &str| {
+            let found = entry.chars().nth(position);
+
+            match found {
+                Some(found) => PermconError::InvalidChar {
+                    input: entry.to_string(),
+                    position,
+                    found,
+                    expected: expected.to_string(),
+                },
+                None => PermconError::UnexpectedEnd {
+                    input: entry.to_string(),
+                    expected: expected.to_string(),
+                },
+            }
+        };
+
+        let tag_name = match fields[0] {
+            "u" | "user" => "user",
+            "g" | "group" => "group",
+            "m" | "mask" => "mask",
+            "o" | "other" => "other",
+            _ => {
+                return Err(invalid_char(
+                    positions[0],
+                    "one of 'user', 'group', 'mask', 'other', 'default'",
+                ))
+            }
+        };
+
+        let (qualifier, perms_index) = match (tag_name, fields.len()) {
+            ("mask" | "other", 2) => ("", 1),
+            (_, 3) => (fields[1], 2),
+            (_, length) if length < 3 => {
+                return Err(PermconError::UnexpectedEnd {
+                    input: entry.to_string(),
+                    expected: String::from("':' followed by the permissions"),
+                })
+            }
+            _ => {
+                let position = positions[3] - 1;
+                return Err(invalid_char(position, "the end of the entry"));
+            }
+        };
+
+        let tag = match (tag_name, qualifier) {
+            ("user", "") => AclTag::UserObj,
+            ("user", name) => AclTag::User(name.to_string()),
+            ("group", "") => AclTag::GroupObj,
+            ("group", name) => AclTag::Group(name.to_string()),
+            ("mask", "") => AclTag::Mask,
+            ("other", "") => AclTag::Other,
+            _ => return Err(invalid_char(positions[1], "':' (no qualifier)")),
+        };
+
+        let permission = parse_perms(fields[perms_index]).map_err(|offset| {
+            invalid_char(positions[perms_index] + offset, "one of 'r', 'w', 'x', '-'")
+        })?;
+
+        Ok(AclEntry {
+            default: is_default,
+            tag,
+            permission,
+        })
+    }
+}
+
+/// Parses `rwx`-style permissions in any order, or a single octal digit.
+/// Returns the offset of the offending char on failure.
+fn parse_perms(perms: &str) -> Result<GroupPermission, usize> {
+    if let Some(digit) = perms.parse::<u8>().ok().filter(|_| perms.len() == 1) {
+        return GroupPermission::from_octal_digit(digit, false).map_err(|_| 0);
+    }
+
+    if perms.is_empty() {
+        return Err(0);
+    }
+
+    let mut permission = GroupPermission::from_octal_digit(0, false).unwrap();
+
+    for (offset, ch) in perms.chars().enumerate() {
+        match ch {
+            'r' => permission.read = true,
+            'w' => permission.write = true,
+            'x' => permission.execute = true,
+            '-' => {}
+            _ => return Err(offset),
+        }
+    }
+
+    Ok(permission)
+}
+
+/// Represents a POSIX access control list as printed by `getfacl`.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::acl::{Acl, AclTag};
+///
+/// let acl = Acl::from_str("\
+/// ## file: shared
+/// user::rwx
+/// user:joe:rwx\t#effective:r-x
+/// group::rwx\t#effective:r-x
+/// mask::r-x
+/// other::---
+/// ").unwrap();
+///
+/// let joe = acl.find(false, &AclTag::User(String::from("joe"))).unwrap();
+/// assert_eq!(acl.effective_permission(joe).to_symbolic_str(&'x'), "r-x");
+///
+/// // `ls -l` shows the mask as the group triplet
+/// assert_eq!(acl.to_file_permission().to_symbolic_str(), "-rwxr-x---");
+/// ```
+#[derive(Debug, PartialEq, Clone)]
+pub struct Acl {
+    /// The `# file:` header.
+    pub file: Option<String>,
+    /// The `# owner:` header.
+    pub owner: Option<String>,
+    /// The `# group:` header.
+    pub group: Option<String>,
+    /// The `# flags:` header (e.g., `-s-`) as `[SUID, SGID, StickyBit]`.
+    pub special: [SpecialPermission; 3],
+    /// The access entries followed by the default entries.
+    pub entries: Vec<AclEntry>,
+}
+
+impl Acl {
+    /// Returns the entry with the given tag.
+    pub fn find(&self, default: bool, tag: &AclTag) -> Option<&AclEntry> {
+        self.entries
+            .iter()
+            .find(|entry| entry.default == default && entry.tag == *tag)
+    }
+
+    /// Returns the permission `entry` actually grants, i.e., masked by the
+    /// `mask::` entry (of the same access or default list) if it is a group
+    /// class entry.
+    pub fn effective_permission(&self, entry: &AclEntry) -> GroupPermission {
+        let mask = self
+            .find(entry.default, &AclTag::Mask)
+            .filter(|_| entry.tag.is_group_class());
+
+        match mask {
+            Some(mask) => GroupPermission {
+                read: entry.permission.read && mask.permission.read,
+                write: entry.permission.write && mask.permission.write,
+                execute: entry.permission.execute && mask.permission.execute,
+                special: false,
+            },
+            None => entry.permission.clone(),
+        }
+    }
+
+    /// Returns the permission `ls -l` shows for the file: the owner and other
+    /// entries, with the mask (or the owning group without a mask) as the
+    /// group triplet. The file type is unknown.
+    pub fn to_file_permission(&self) -> FilePermission {
+        let digit = |tag: &AclTag| {
+            self.find(false, tag)
+                .map_or(0, |entry| entry.permission.to_octal_digit() as u16)
+        };
+
+        let group = match self.find(false, &AclTag::Mask) {
+            Some(mask) => mask.permission.to_octal_digit() as u16,
+            None => digit(&AclTag::GroupObj),
+        };
+
+        let special = self
+            .special
+            .iter()
+            .zip([0o4000, 0o2000, 0o1000])
+            .filter(|(perm, _)| **perm != SpecialPermission::Nil)
+            .fold(0, |bits, (_, bit)| bits | bit);
+
+        FilePermission::from_bits(
+            special | digit(&AclTag::UserObj) << 6 | group << 3 | digit(&AclTag::Other),
+            FileType::Unknown,
+        )
+    }
+
+    /// Applies `setfacl -m` entries (e.g., `u:joe:rw,d:g:staff:rx`): entries
+    /// with the same tag are replaced, others are added. Like `setfacl`, the
+    /// mask is recalculated as the union of the group class entries unless
+    /// `entries` set it explicitly.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use permcon::acl::Acl;
+    ///
+    /// let mut acl = Acl::from_str("user::rw-\ngroup::r--\nother::---").unwrap();
+    /// acl.modify("u:joe:rw,g:staff:x").unwrap();
+    ///
+    /// assert_eq!(acl.to_file_permission().to_symbolic_str(), "-rw-rwx---");
+    /// ```
+    pub fn modify(&mut self, entries: &str) -> Result<(), PermconError> {
+        let entries = entries
+            .split(',')
+            .map(AclEntry::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for default in [false, true] {
+            let list: Vec<&AclEntry> = entries
+                .iter()
+                .filter(|entry| entry.default == default)
+                .collect();
+
+            if list.is_empty() {
+                continue;
+            }
+
+            for entry in &list {
+                match self
+                    .entries
+                    .iter_mut()
+                    .find(|current| current.default == default && current.tag == entry.tag)
+                {
+                    Some(current) => current.permission = entry.permission.clone(),
+                    None => self.entries.push((*entry).clone()),
+                }
+            }
+
+            if !list.iter().any(|entry| entry.tag == AclTag::Mask) {
+                self.recalculate_mask(default);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets the mask to the union of the group class entries. Adds a mask
+    /// only if the ACL has named entries, a minimal ACL doesn't need one.
+    fn recalculate_mask(&mut self, default: bool) {
+        let group_class: Vec<&AclEntry> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.default == default && entry.tag.is_group_class())
+            .collect();
+
+        let has_named_entries = group_class
+            .iter()
+            .any(|entry| entry.tag != AclTag::GroupObj);
+
+        let union = group_class.iter().fold(
+            GroupPermission::from_octal_digit(0, false).unwrap(),
+            |union, entry| GroupPermission {
+                read: union.read || entry.permission.read,
+                write: union.write || entry.permission.write,
+                execute: union.execute || entry.permission.execute,
+                special: false,
+            },
+        );
+
+        match self
+            .entries
+            .iter_mut()
+            .find(|entry| entry.default == default && entry.tag == AclTag::Mask)
+        {
+            Some(mask) => mask.permission = union,
+            None if has_named_entries => self.entries.push(AclEntry {
+                default,
+                tag: AclTag::Mask,
+                permission: union,
+            }),
+            None => {}
+        }
+    }
+}
+
+impl FromStr for Acl {
+    type Err = PermconError;
+
+    /// Tries to parse the output of `getfacl` for a single file. Blank lines
+    /// and `#effective:` comments are ignored.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut acl = Acl {
+            file: None,
+            owner: None,
+            group: None,
+            special: [
+                SpecialPermission::Nil,
+                SpecialPermission::Nil,
+                SpecialPermission::Nil,
+            ],
+            entries: Vec::new(),
+        };
+
+        for line in text.lines().map(str::trim) {
+            if let Some(header) = line.strip_prefix('#') {
+                let value = |name: &str| {
+                    header
+                        .trim()
+                        .strip_prefix(name)
+                        .map(|value| value.trim().to_string())
+                };
+
+                if let Some(file) = value("file:") {
+                    acl.file = Some(file);
+                } else if let Some(owner) = value("owner:") {
+                    acl.owner = Some(owner);
+                } else if let Some(group) = value("group:") {
+                    acl.group = Some(group);
+                } else if let Some(flags) = value("flags:") {
+                    acl.special = parse_flags(&flags);
+                }
+
+                continue;
+            }
+
+            let entry = match line.find('#') {
+                Some(index) => line[..index].trim_end(),
+                None => line,
+            };
+
+            if !entry.is_empty() {
+                acl.entries.push(AclEntry::from_str(entry)?);
+            }
+        }
+
+        for tag in [AclTag::UserObj, AclTag::GroupObj, AclTag::Other] {
+            if acl.find(false, &tag).is_none() {
+                return Err(PermconError::MissingAclEntry {
+                    input: text.to_string(),
+                    entry: format!("{tag}:"),
+                });
+            }
+        }
+
+        Ok(acl)
+    }
+}
+
+impl fmt::Display for Acl {
+    /// Formats the ACL like `getfacl`, including `#effective:` comments where
+    /// the mask takes away permissions.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (name, value) in [
+            ("file", &self.file),
+            ("owner", &self.owner),
+            ("group", &self.group),
+        ] {
+            if let Some(value) = value {
+                writeln!(f, "# {name}: {value}")?;
+            }
+        }
+
+        if self
+            .special
+            .iter()
+            .any(|perm| *perm != SpecialPermission::Nil)
+        {
+            let flags: String = self
+                .special
+                .iter()
+                .zip(['s', 's', 't'])
+                .map(|(perm, ch)| {
+                    if *perm == SpecialPermission::Nil {
+                        '-'
+                    } else {
+                        ch
+                    }
+                })
+                .collect();
+
+            writeln!(f, "# flags: {flags}")?;
+        }
+
+        for entry in &self.entries {
+            let effective = self.effective_permission(entry);
+
+            if effective == entry.permission {
+                writeln!(f, "{entry}")?;
+            } else {
+                writeln!(f, "{entry}\t#effective:{}", effective.to_symbolic_str(&'x'))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parses the `# flags:` header (e.g., `-st`) of `getfacl`.
+fn parse_flags(flags: &str) -> [SpecialPermission; 3] {
+    let is_set = |index: usize| flags.chars().nth(index).is_some_and(|ch| ch != '-');
+
+    [
+        SpecialPermission::SUID,
+        SpecialPermission::SGID,
+        SpecialPermission::StickyBit,
+    ]
+    .into_iter()
+    .enumerate()
+    .map(|(index, perm)| {
+        if is_set(index) {
+            perm
+        } else {
+            SpecialPermission::Nil
+        }
+    })
+    .collect::<Vec<_>>()
+    .try_into()
+    .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GETFACL_OUTPUT: &str = "\
+# file: srv/project
+# owner: lisa
+# group: staff
+# flags: -s-
+user::rwx
+user:joe:rwx\t\t\t#effective:r-x
+group::rwx\t\t\t#effective:r-x
+group:cool:r-x
+mask::r-x
+other::r-x
+default:user::rwx
+default:user:joe:rwx\t\t#effective:r-x
+default:group::r-x
+default:mask::r-x
+default:other::---
+";
+
+    #[test]
+    fn test_parse_getfacl() {
+        let acl = Acl::from_str(GETFACL_OUTPUT).unwrap();
+
+        assert_eq!(acl.file.as_deref(), Some("srv/project"));
+        assert_eq!(acl.owner.as_deref(), Some("lisa"));
+        assert_eq!(acl.entries.len(), 11);
+        assert_eq!(acl.to_file_permission().to_symbolic_str(), "-rwxr-sr-x");
+        assert_eq!(
+            acl.to_string(),
+            GETFACL_OUTPUT.replace("\t\t\t", "\t").replace("\t\t", "\t")
+        );
+
+        let effective = |default: bool, tag: AclTag| {
+            let entry = acl.find(default, &tag).unwrap();
+            acl.effective_permission(entry).to_symbolic_str(&'x')
+        };
+
+        assert_eq!(effective(false, AclTag::UserObj), "rwx");
+        assert_eq!(effective(false, AclTag::User(String::from("joe"))), "r-x");
+        assert_eq!(effective(false, AclTag::GroupObj), "r-x");
+        assert_eq!(effective(false, AclTag::Other), "r-x");
+        assert_eq!(effective(true, AclTag::User(String::from("joe"))), "r-x");
+        assert_eq!(effective(true, AclTag::Other), "---");
+    }
+
+    #[test]
+    fn test_parse_entries() {
+        let test_cases = [
+            ("u::rw", false, AclTag::UserObj, "rw-"),
+            (
+                "user:1001:7",
+                false,
+                AclTag::User(String::from("1001")),
+                "rwx",
+            ),
+            (
+                "g:staff:xr",
+                false,
+                AclTag::Group(String::from("staff")),
+                "r-x",
+            ),
+            ("m:r", false, AclTag::Mask, "r--"),
+            ("d:o::-", true, AclTag::Other, "---"),
+            ("default:group::r-x", true, AclTag::GroupObj, "r-x"),
+        ];
+
+        for (entry, default, tag, perms) in test_cases {
+            let parsed = AclEntry::from_str(entry).unwrap();
+
+            assert_eq!(parsed.default, default, "{entry}");
+            assert_eq!(parsed.tag, tag, "{entry}");
+            assert_eq!(parsed.permission.to_symbolic_str(&'x'), perms, "{entry}");
+        }
+
+        // (entry, error position)
+        let test_cases: [(&str, usize); 6] = [
+            ("x::rw", 0),
+            ("u:joe:rwz", 8),
+            ("m:joe:r", 2),
+            ("u:joe", 5),
+            ("u:joe:r:w", 7),
+            ("g::", 3),
+        ];
+
+        for (entry, position) in test_cases {
+            let error = AclEntry::from_str(entry).unwrap_err();
+            assert_eq!(error.position(), Some(position), "{entry}");
+        }
+    }
+
+    #[test]
+    fn test_modify() {
+        let mut acl = Acl::from_str("user::rw-\ngroup::r--\nother::---").unwrap();
+
+        acl.modify("g::rw").unwrap();
+        assert_eq!(acl.find(false, &AclTag::Mask), None);
+        assert_eq!(acl.to_file_permission().to_octal_str(), "0660");
+
+        acl.modify("u:joe:rwx,d:u::rwx").unwrap();
+        assert_eq!(acl.to_file_permission().to_octal_str(), "0670");
+        assert!(acl.find(true, &AclTag::Mask).is_none());
+
+        acl.modify("m::r").unwrap();
+        let joe = acl.find(false, &AclTag::User(String::from("joe"))).unwrap();
+        assert_eq!(acl.effective_permission(joe).to_symbolic_str(&'x'), "r--");
+        assert_eq!(acl.to_file_permission().to_octal_str(), "0640");
+
+        assert!(Acl::from_str("user::rwx\nother::---").is_err());
+    }
+}
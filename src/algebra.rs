@@ -0,0 +1,242 @@
+use crate::perm::{FilePermission, GroupPermission};
+use std::cmp::Ordering;
+
+impl FilePermission {
+    /// Returns `true` if every bit (including the special bits) of `self` is
+    /// also set in `other`, i.e., `self` is at most as permissive as `other`.
+    /// The file types are not compared.
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let limit = FilePermission::try_from("0750").unwrap();
+    ///
+    /// assert!(FilePermission::try_from("-rwxr-----").unwrap().is_subset(&limit));
+    /// assert!(!FilePermission::try_from("2750").unwrap().is_subset(&limit));
+    /// ```
+    pub fn is_subset(&self, other: &FilePermission) -> bool {
+        self.to_bits() & !other.to_bits() == 0
+    }
+
+    /// Returns `true` if every bit of `other` is also set in `self`.
+    pub fn is_superset(&self, other: &FilePermission) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns the bits set in either permission. The file type and source
+    /// format are taken from `self`, like in all the set operations.
+    pub fn union(&self, other: &FilePermission) -> Self {
+        self.with_bits(self.to_bits() | other.to_bits())
+    }
+
+    /// Returns the bits set in both permissions.
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let a = FilePermission::try_from("4755").unwrap();
+    /// let b = FilePermission::try_from("0640").unwrap();
+    ///
+    /// assert_eq!(a.intersection(&b).to_octal_str(), "0640");
+    /// assert_eq!(a.union(&b).to_octal_str(), "4755");
+    /// assert_eq!(a.difference(&b).to_octal_str(), "4115");
+    /// assert_eq!(a.complement().to_octal_str(), "3022");
+    /// ```
+    pub fn intersection(&self, other: &FilePermission) -> Self {
+        self.with_bits(self.to_bits() & other.to_bits())
+    }
+
+    /// Returns the bits of `self` that are not set in `other`.
+    pub fn difference(&self, other: &FilePermission) -> Self {
+        self.with_bits(self.to_bits() & !other.to_bits())
+    }
+
+    /// Returns the bits that are not set in `self`.
+    pub fn complement(&self) -> Self {
+        self.with_bits(!self.to_bits() & 0o7777)
+    }
+
+    /// Orders permissions by inclusion of their bits: `Less` if `self` is a
+    /// proper subset of `other`, `None` if neither includes the other. Unlike
+    /// `==`, the file type and source format are ignored, which is why this
+    /// isn't a `PartialOrd` impl.
+    ///
+    /// ```rust
+    /// use std::cmp::Ordering;
+    /// use permcon::FilePermission;
+    ///
+    /// let perm = |perm| FilePermission::try_from(perm).unwrap();
+    ///
+    /// assert_eq!(perm("0640").partial_cmp_bits(&perm("0750")), Some(Ordering::Less));
+    /// assert_eq!(perm("drwxr-x---").partial_cmp_bits(&perm("0750")), Some(Ordering::Equal));
+    /// assert_eq!(perm("0640").partial_cmp_bits(&perm("0704")), None);
+    /// ```
+    pub fn partial_cmp_bits(&self, other: &FilePermission) -> Option<Ordering> {
+        match (self.is_subset(other), self.is_superset(other)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+impl GroupPermission {
+    /// Returns `true` if every bit (including the special one) of `self` is
+    /// also set in `other`.
+    pub fn is_subset(&self, other: &GroupPermission) -> bool {
+        self.to_bool_array()
+            .into_iter()
+            .zip(other.to_bool_array())
+            .all(|(is_set, is_set_in_other)| !is_set || is_set_in_other)
+    }
+
+    /// Returns `true` if every bit of `other` is also set in `self`.
+    pub fn is_superset(&self, other: &GroupPermission) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Returns the bits set in either permission.
+    pub fn union(&self, other: &GroupPermission) -> Self {
+        self.combine(other, |a, b| a || b)
+    }
+
+    /// Returns the bits set in both permissions.
+    pub fn intersection(&self, other: &GroupPermission) -> Self {
+        self.combine(other, |a, b| a && b)
+    }
+
+    /// Returns the bits of `self` that are not set in `other`.
+    pub fn difference(&self, other: &GroupPermission) -> Self {
+        self.combine(other, |a, b| a && !b)
+    }
+
+    /// Returns the bits that are not set in `self`.
+    pub fn complement(&self) -> Self {
+        self.combine(self, |a, _| !a)
+    }
+
+    /// Returns `[read, write, execute, special]`.
+    fn to_bool_array(&self) -> [bool; 4] {
+        [self.read, self.write, self.execute, self.special]
+    }
+
+    fn combine(&self, other: &GroupPermission, op: fn(bool, bool) -> bool) -> Self {
+        let [read, write, execute, special] = self.to_bool_array();
+        let [other_read, other_write, other_execute, other_special] = other.to_bool_array();
+
+        GroupPermission {
+            read: op(read, other_read),
+            write: op(write, other_write),
+            execute: op(execute, other_execute),
+            special: op(special, other_special),
+        }
+    }
+}
+
+impl PartialOrd for GroupPermission {
+    /// Orders permissions by inclusion, see [`GroupPermission::is_subset`].
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        match (self.is_subset(other), self.is_superset(other)) {
+            (true, true) => Some(Ordering::Equal),
+            (true, false) => Some(Ordering::Less),
+            (false, true) => Some(Ordering::Greater),
+            (false, false) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::filetype::FileType;
+
+    #[test]
+    fn test_set_operations_match_bit_operations() {
+        let perms: Vec<FilePermission> = [0o0000, 0o0750, 0o0755, 0o1777, 0o4711, 0o2640, 0o7777]
+            .into_iter()
+            .map(|bits| FilePermission::from_bits(bits, FileType::Directory))
+            .collect();
+
+        for a in &perms {
+            let (a_bits, a_groups) = (a.to_bits(), a.to_perm_group_array());
+
+            assert_eq!(a.complement().to_bits(), !a_bits & 0o7777);
+            assert_eq!(a.complement().complement(), *a);
+
+            for b in &perms {
+                let (b_bits, b_groups) = (b.to_bits(), b.to_perm_group_array());
+
+                assert_eq!(a.union(b).to_bits(), a_bits | b_bits);
+                assert_eq!(a.intersection(b).to_bits(), a_bits & b_bits);
+                assert_eq!(a.difference(b).to_bits(), a_bits & !b_bits);
+                assert_eq!(a.is_subset(b), a_bits & b_bits == a_bits);
+                assert_eq!(
+                    a.is_subset(b),
+                    a.partial_cmp_bits(b)
+                        .is_some_and(|ordering| ordering.is_le())
+                );
+                assert_eq!(
+                    a.is_superset(b),
+                    a.partial_cmp_bits(b)
+                        .is_some_and(|ordering| ordering.is_ge())
+                );
+
+                // the group permissions agree with the file permissions
+                let union = a.union(b);
+                let intersection = a.intersection(b);
+                let difference = a.difference(b);
+
+                for index in 0..3 {
+                    let (a_group, b_group) = (a_groups[index], b_groups[index]);
+
+                    assert_eq!(a_group.union(b_group), *union.to_perm_group_array()[index]);
+                    assert_eq!(
+                        a_group.intersection(b_group),
+                        *intersection.to_perm_group_array()[index]
+                    );
+                    assert_eq!(
+                        a_group.difference(b_group),
+                        *difference.to_perm_group_array()[index]
+                    );
+                    assert_eq!(a_group.is_subset(b_group), a_group <= b_group);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_partial_cmp_bits() {
+        let octal = |perm| FilePermission::try_from(perm).unwrap();
+
+        assert_eq!(
+            octal("0700").partial_cmp_bits(&octal("0750")),
+            Some(Ordering::Less)
+        );
+        assert_eq!(
+            octal("4750").partial_cmp_bits(&octal("0750")),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(
+            octal("0750").partial_cmp_bits(&octal("0750")),
+            Some(Ordering::Equal)
+        );
+        assert_eq!(octal("0700").partial_cmp_bits(&octal("0070")), None);
+
+        // same bits, different file type: equal bits but not `==`
+        let symbolic = FilePermission::try_from("drwxr-x---").unwrap();
+        assert_eq!(
+            symbolic.partial_cmp_bits(&octal("0750")),
+            Some(Ordering::Equal)
+        );
+        assert_ne!(symbolic, octal("0750"));
+
+        // `partial_cmp_bits` is antisymmetric
+        for (a, b) in [("0640", "0750"), ("1777", "0777"), ("0700", "0070")] {
+            assert_eq!(
+                octal(a).partial_cmp_bits(&octal(b)),
+                octal(b).partial_cmp_bits(&octal(a)).map(Ordering::reverse)
+            );
+        }
+    }
+}
@@ -0,0 +1,387 @@
+use crate::error::PermconError;
+use std::str::FromStr;
+
+/// The class(es) of users a chmod clause applies to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Who {
+    User,
+    Group,
+    Other,
+    All,
+}
+
+impl Who {
+    /// Returns the permission bits (including the related special bit) that
+    /// belong to this class.
+    fn mask(&self) -> u16 {
+        match self {
+            Who::User => 0o4700,
+            Who::Group => 0o2070,
+            Who::Other => 0o1007,
+            Who::All => 0o7777,
+        }
+    }
+
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            'u' => Some(Who::User),
+            'g' => Some(Who::Group),
+            'o' => Some(Who::Other),
+            'a' => Some(Who::All),
+            _ => None,
+        }
+    }
+}
+
+/// The operator of a chmod action: `+`, `-` or `=`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Op {
+    Add,
+    Remove,
+    Set,
+}
+
+/// A single permission symbol of a chmod action.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Perm {
+    /// `r`
+    Read,
+    /// `w`
+    Write,
+    /// `x`
+    Execute,
+    /// `X`: execute only if the target is a directory or some execute bit is
+    /// already set.
+    ConditionalExecute,
+    /// `s`: set-user-ID or set-group-ID depending on the class.
+    SetId,
+    /// `t`
+    Sticky,
+}
+
+impl Perm {
+    fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            'r' => Some(Perm::Read),
+            'w' => Some(Perm::Write),
+            'x' => Some(Perm::Execute),
+            'X' => Some(Perm::ConditionalExecute),
+            's' => Some(Perm::SetId),
+            't' => Some(Perm::Sticky),
+            _ => None,
+        }
+    }
+}
+
+/// The right hand side of a chmod action: either a list of permission
+/// symbols (`u+rw`) or a class to copy the permissions from (`g=u`).
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PermSource {
+    Perms(Vec<Perm>),
+    Copy(Who),
+}
+
+/// An operator followed by its permissions, e.g., the `+x` of `u+x`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Action {
+    pub op: Op,
+    pub source: PermSource,
+}
+
+/// A single comma separated clause of a chmod expression, e.g., `ug+rw-x`.
+/// An empty `who` list means the clause applies to everyone.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Clause {
+    pub who: Vec<Who>,
+    pub actions: Vec<Action>,
+}
+
+/// Represents a parsed symbolic chmod mode expression (e.g., `u+x,g-w,o=`).
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::chmod::{Action, ChmodExpr, Clause, Op, Perm, PermSource, Who};
+///
+/// let expr = ChmodExpr::from_str("u+x,g=u").unwrap();
+///
+/// assert_eq!(expr, ChmodExpr {
+///     clauses: vec![
+///         Clause {
+///             who: vec![Who::User],
+///             actions: vec![Action { op: Op::Add, source: PermSource::Perms(vec![Perm::Execute]) }],
+///         },
+///         Clause {
+///             who: vec![Who::Group],
+///             actions: vec![Action { op: Op::Set, source: PermSource::Copy(Who::User) }],
+///         },
+///     ],
+/// });
+///
+/// assert_eq!(expr.apply_to_bits(0o644, false), 0o774);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ChmodExpr {
+    pub clauses: Vec<Clause>,
+}
+
+impl ChmodExpr {
+    /// Applies the expression to the given permission bits (e.g., `0o1755`)
+    /// and returns the resulting bits. `is_directory` decides whether the
+    /// conditional execute (`X`) always grants execute.
+    pub fn apply_to_bits(&self, bits: u16, is_directory: bool) -> u16 {
+        self.clauses.iter().fold(bits & 0o7777, |bits, clause| {
+            clause.apply_to_bits(bits, is_directory)
+        })
+    }
+}
+
+impl Clause {
+    fn apply_to_bits(&self, mut bits: u16, is_directory: bool) -> u16 {
+        let who_mask = if self.who.is_empty() {
+            Who::All.mask()
+        } else {
+            self.who.iter().fold(0, |mask, who| mask | who.mask())
+        };
+
+        for action in &self.actions {
+            let value = action.source.to_bits(bits, is_directory) & who_mask;
+
+            bits = match action.op {
+                Op::Add => bits | value,
+                Op::Remove => bits & !value,
+                Op::Set => (bits & !who_mask) | value,
+            };
+        }
+
+        bits
+    }
+}
+
+impl PermSource {
+    /// Returns the bits this source stands for across all classes. The
+    /// caller narrows them down with the clause's class mask.
+    fn to_bits(&self, current: u16, is_directory: bool) -> u16 {
+        match self {
+            PermSource::Copy(who) => {
+                let rwx = match who {
+                    Who::User => (current >> 6) & 0o7,
+                    Who::Group => (current >> 3) & 0o7,
+                    Who::Other | Who::All => current & 0o7,
+                };
+
+                rwx * 0o111
+            }
+            PermSource::Perms(perms) => perms.iter().fold(0, |bits, perm| {
+                bits | match perm {
+                    Perm::Read => 0o444,
+                    Perm::Write => 0o222,
+                    Perm::Execute => 0o111,
+                    Perm::ConditionalExecute if is_directory || current & 0o111 != 0 => 0o111,
+                    Perm::ConditionalExecute => 0,
+                    Perm::SetId => 0o6000,
+                    Perm::Sticky => 0o1000,
+                }
+            }),
+        }
+    }
+}
+
+impl FromStr for ChmodExpr {
+    type Err = PermconError;
+
+    /// Tries to parse a chmod expression following the POSIX grammar:
+    ///
+    /// ```text
+    /// mode   ::= clause [, clause] ...
+    /// clause ::= [who ...] [action ...] action
+    /// action ::= op [perm ...] | op permcopy
+    /// who    ::= a | u | g | o
+    /// op     ::= + | - | =
+    /// perm   ::= r | w | x | X | s | t
+    /// permcopy ::= u | g | o
+    /// ```
+    fn from_str(expr: &str) -> Result<Self, Self::Err> {
+        let mut offset = 0;
+        let mut clauses = Vec::new();
+
+        for clause in expr.split(',') {
+            clauses.push(parse_clause(expr, clause, offset)?);
+            offset += clause.chars().count() + 1;
+        }
+
+        Ok(ChmodExpr { clauses })
+    }
+}
+
+fn parse_op(ch: char) -> Option<Op> {
+    match ch {
+        '+' => Some(Op::Add),
+        '-' => Some(Op::Remove),
+        '=' => Some(Op::Set),
+        _ => None,
+    }
+}
+
+/// Parses a single `clause` that starts at the char position `offset` of the
+/// whole `expr`. Error positions are relative to `expr`.
+fn parse_clause(expr: &str, clause: &str, offset: usize) -> Result<Clause, PermconError> {
+    let mut chars = clause.chars().peekable();
+    let mut position = offset;
+    let mut who = Vec::new();
+
+    while let Some(class) = chars.peek().and_then(|ch| Who::from_char(*ch)) {
+        who.push(class);
+        chars.next();
+        position += 1;
+    }
+
+    let mut actions: Vec<Action> = Vec::new();
+
+    while let Some(ch) = chars.next() {
+        let op = parse_op(ch).ok_or_else(|| {
+            let expected = match actions.last() {
+                None => "a class (u, g, o, a) or an operator (+, -, =)",
+                Some(Action {
+                    source: PermSource::Copy(_),
+                    ..
+                }) => "an operator (+, -, =)",
+                Some(_) => "a permission (r, w, x, X, s, t) or an operator (+, -, =)",
+            };
+
+            PermconError::InvalidChar {
+                input: expr.to_string(),
+                position,
+                found: ch,
+                expected: expected.to_string(),
+            }
+        })?;
+        position += 1;
+
+        let copy_from = chars.peek().and_then(|ch| match ch {
+            'u' | 'g' | 'o' => Who::from_char(*ch),
+            _ => None,
+        });
+
+        let source = match copy_from {
+            Some(class) => {
+                chars.next();
+                position += 1;
+                PermSource::Copy(class)
+            }
+            None => {
+                let mut perms = Vec::new();
+
+                while let Some(perm) = chars.peek().and_then(|ch| Perm::from_char(*ch)) {
+                    perms.push(perm);
+                    chars.next();
+                    position += 1;
+                }
+
+                PermSource::Perms(perms)
+            }
+        };
+
+        actions.push(Action { op, source });
+    }
+
+    if actions.is_empty() {
+        return Err(PermconError::MissingOperator {
+            input: expr.to_string(),
+            position,
+        });
+    }
+
+    Ok(Clause { who, actions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{filetype::FileType, perm::FilePermission};
+
+    fn apply(expr: &str, bits: u16) -> u16 {
+        ChmodExpr::from_str(expr)
+            .unwrap()
+            .apply_to_bits(bits, false)
+    }
+
+    #[test]
+    fn test_apply_to_bits() {
+        let test_cases: [(&str, u16, u16); 14] = [
+            ("u+x,g-w,o=", 0o664, 0o740),
+            ("a+rX", 0o600, 0o644),
+            ("a+rX", 0o700, 0o755),
+            ("+x", 0o644, 0o755),
+            ("ug=rw", 0o4751, 0o661),
+            ("u=g", 0o640, 0o440),
+            ("go=u-w", 0o755, 0o755),
+            ("u+s,g+s", 0o755, 0o6755),
+            ("+t", 0o777, 0o1777),
+            ("u+t", 0o777, 0o777),
+            ("o-t", 0o1777, 0o777),
+            ("o+s", 0o755, 0o755),
+            ("u=rwx,g=rx,o=", 0o0, 0o750),
+            ("u+,g-", 0o640, 0o640),
+        ];
+
+        for (expr, input, output) in test_cases {
+            assert_eq!(apply(expr, input), output, "{expr} on {input:o}");
+        }
+    }
+
+    #[test]
+    fn test_conditional_execute_by_filetype() {
+        // (filetype char, result of `a+X` on 0644, result of `a+X` on 0744)
+        let test_cases: [(char, &str, &str); 8] = [
+            ('-', "0644", "0755"),
+            ('d', "0755", "0755"),
+            ('l', "0644", "0755"),
+            ('b', "0644", "0755"),
+            ('c', "0644", "0755"),
+            ('s', "0644", "0755"),
+            ('p', "0644", "0755"),
+            ('D', "0644", "0755"),
+        ];
+
+        for (filetype, without_execute, with_execute) in test_cases {
+            for (symbolic, output) in [("rw-r--r--", without_execute), ("rwxr--r--", with_execute)]
+            {
+                let perm = FilePermission {
+                    filetype: FileType::from_char(filetype),
+                    ..FilePermission::try_from(symbolic).unwrap()
+                };
+
+                let result = perm.apply("a+X").unwrap();
+
+                assert_eq!(result.to_octal_str(), output, "{filetype}{symbolic}");
+                assert_eq!(result.filetype.to_char(), filetype);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expr in ["", "u", "u+x,", "u+z", "k+x", "u+x,,g-w", "u=gx"] {
+            assert!(ChmodExpr::from_str(expr).is_err(), "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_error_positions() {
+        let test_cases: [(&str, usize); 6] = [
+            ("", 0),
+            ("u", 1),
+            ("u+x,", 4),
+            ("u+x,g-w,o+z", 10),
+            ("u+x,,g-w", 4),
+            ("go=ux", 4),
+        ];
+
+        for (expr, position) in test_cases {
+            let error = ChmodExpr::from_str(expr).unwrap_err();
+
+            assert_eq!(error.input(), expr);
+            assert_eq!(error.position(), Some(position), "{expr}");
+        }
+    }
+}
@@ -1,15 +1,53 @@
-use clap::Parser;
+use clap::{
+    builder::PossibleValuesParser, builder::TypedValueParser, error::ErrorKind, CommandFactory,
+    Parser, Subcommand,
+};
 use yansi::Paint;
 
-use permcon::perm::{FilePermission, GroupPermission, SourceFormat, SpecialPermission};
+use permcon::access::{Access, Credentials, Owner};
+use permcon::error::PermconError;
+use permcon::filetype::FileType;
+use permcon::find::PermMatcher;
+use permcon::fs::{find_permissions, set_permission, ModeSpec};
+use permcon::git::GitEntry;
+use permcon::lint::{lint, lint_archive_entry, Severity};
+use permcon::ls::{LsAttribute, LsEntry};
+use permcon::perm::{
+    FilePermission, GroupPermission, SourceFormat, SpecialPermission, JSON_SCHEMA,
+};
+use permcon::render::{Format, Template};
+use permcon::tar::{normalize_tar, read_tar_entries, TarNormalizeOptions};
+use permcon::umask::Umask;
+use permcon::zip::{lint_zip_entry, read_zip_entries};
+use std::{
+    fs::File,
+    io::BufWriter,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
 /// A CLI to parse Linux file system permissions and convert them
 /// between symbolic and octal formats.
 #[derive(Debug, Parser)]
-#[command(version)]
+#[command(
+    version,
+    args_conflicts_with_subcommands = true,
+    subcommand_negates_reqs = true
+)]
 struct Cli {
-    /// The permission string, either octal or symbolic
-    permission: String,
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// The permission strings, either octal or symbolic. More than one are
+    /// converted in order, see `--stdin`
+    #[arg(required_unless_present_any = ["umask", "path", "json_schema", "ls", "stdin"])]
+    permissions: Vec<String>,
+
+    /// Read the permissions from stdin, one per line. A line that fails to
+    /// parse is reported and skipped, and the exit code is 1 if any did.
+    /// JSON is printed as JSON Lines
+    #[arg(long, conflicts_with_all = ["permissions", "umask", "path", "ls"])]
+    stdin: bool,
 
     /// Print detailed analysis
     #[arg(short, long, conflicts_with = "json")]
@@ -23,38 +61,359 @@ struct Cli {
     #[arg(short, long)]
     json: bool,
 
-    /// Beautify JSON
-    #[arg(short, long, requires = "json")]
+    /// Beautify JSON, of `--json` or `--to json`
+    #[arg(short, long)]
     pretty: bool,
+
+    /// Apply a chmod expression (e.g., `u+x,g-w,o=`) to the permission
+    #[arg(long, value_name = "EXPR", allow_hyphen_values = true)]
+    apply: Option<String>,
+
+    /// Show the permissions new files and directories get with this umask
+    /// (e.g., `027` or `u=rwx,g=rx,o=`)
+    #[arg(long, conflicts_with_all = ["permissions", "analyze", "json", "apply"])]
+    umask: Option<String>,
+
+    /// Read the permission of a file from the filesystem (repeatable)
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["permissions", "apply", "umask"])]
+    path: Vec<PathBuf>,
+
+    /// Read `ls -l` output from stdin and prefix each line with its octal mode
+    #[arg(long, conflicts_with_all = ["permissions", "apply", "umask", "path", "analyze"])]
+    ls: bool,
+
+    /// The notation to print the permission in. Defaults to symbolic for
+    /// octal input and to octal for symbolic input
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        value_parser = PossibleValuesParser::new(Format::ALL.map(Format::name))
+            .map(|name| Format::from_str(&name).unwrap()),
+        conflicts_with_all = ["analyze", "json", "umask", "path", "ls"]
+    )]
+    to: Option<Format>,
+
+    /// Print the permission with a template of `{name}` fields, where a name
+    /// is one of the `--to` formats, `filetype` or `filetype-char` (e.g.,
+    /// `{octal} {symbolic} {filetype}`)
+    #[arg(
+        long,
+        value_name = "TEMPLATE",
+        conflicts_with_all = ["analyze", "json", "to", "umask", "path", "ls"]
+    )]
+    format: Option<String>,
+
+    /// Print the JSON Schema of the `--json` output
+    #[arg(long, exclusive = true)]
+    json_schema: bool,
+}
+
+/// `--pretty` only applies to JSON, which clap can't express as it comes from
+/// either `--json` or `--to json`.
+fn check_pretty(cli: &Cli) -> Result<(), clap::Error> {
+    if cli.pretty && !cli.json && cli.to != Some(Format::Json) {
+        return Err(Cli::command().error(
+            ErrorKind::MissingRequiredArgument,
+            "`--pretty` requires `--json` or `--to json`",
+        ));
+    }
+
+    Ok(())
+}
+
+/// How every converted permission is printed, chosen once from the flags.
+enum Renderer {
+    /// `--analyze`
+    Analysis,
+    /// `--json`
+    Json { pretty: bool },
+    /// `--to`
+    Format(Format),
+    /// `--format`
+    Template(Template),
+    /// `--apply` without an output option: both notations.
+    Applied,
+    /// The other notation of the input.
+    Convert,
+}
+
+impl Renderer {
+    /// In a batch JSON is never beautified, so that every line is a document.
+    fn from_cli(cli: &Cli, is_batch: bool) -> Result<Self, PermconError> {
+        if let Some(template) = &cli.format {
+            return Ok(Renderer::Template(Template::from_str(template)?));
+        }
+
+        let renderer = match cli.to {
+            Some(Format::Json) => Renderer::Json {
+                pretty: cli.pretty && !is_batch,
+            },
+            Some(format) => Renderer::Format(format),
+            None if cli.analyze => Renderer::Analysis,
+            None if cli.json => Renderer::Json {
+                pretty: cli.pretty && !is_batch,
+            },
+            None if cli.apply.is_some() => Renderer::Applied,
+            None => Renderer::Convert,
+        };
+
+        Ok(renderer)
+    }
+
+    fn print(&self, permission: &FilePermission, attribute: Option<LsAttribute>) {
+        match self {
+            Renderer::Analysis => print_analysis(permission, attribute),
+            Renderer::Json { pretty } => println!("{}", permission.to_json(*pretty)),
+            Renderer::Format(format) => println!("{}", permission.render(*format)),
+            Renderer::Template(template) => println!("{}", template.render(permission)),
+            Renderer::Applied => {
+                println!("symbolic     : {}", permission.to_symbolic_str());
+                println!("octal        : {}", permission.to_octal_str());
+            }
+            Renderer::Convert => {
+                let format = if permission.source_format == Some(SourceFormat::Octal) {
+                    Format::Symbolic
+                } else {
+                    Format::Octal
+                };
+
+                println!("{}", permission.render(format));
+            }
+        }
+    }
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Set the permission of files to an absolute mode (`0755`, `rwxr-xr-x`)
+    /// or a chmod expression (`u+x,go-w`)
+    Set {
+        /// The new mode
+        #[arg(allow_hyphen_values = true)]
+        mode: String,
+
+        /// The files to change
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+
+        /// Only report what would change, don't touch the files
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Check which rights a user has on a file with the given permission
+    Check {
+        /// The permission string, either octal or symbolic
+        #[arg(allow_hyphen_values = true)]
+        permission: String,
+
+        /// The owner of the file as `uid:gid`
+        #[arg(long, value_name = "UID:GID")]
+        owner: String,
+
+        /// The requesting user as `uid:gid[,gid...]` (primary group first)
+        #[arg(long = "as", value_name = "UID:GID[,GID...]")]
+        credentials: String,
+
+        /// The requested rights (e.g., `rx`). Exits with 1 if any is denied
+        #[arg(long)]
+        access: Option<String>,
+    },
+
+    /// Report risky permissions of modes (octal or symbolic) or files
+    Lint {
+        /// The permissions or paths to check
+        #[arg(required = true)]
+        targets: Vec<String>,
+
+        /// Exit with 1 if there is a finding of at least this severity
+        /// (`info`, `warning` or `error`)
+        #[arg(long, value_name = "SEVERITY", default_value = "warning")]
+        fail_on: String,
+    },
+
+    /// Show the bits that change between two permissions and the chmod
+    /// expression turning the first into the second
+    Diff {
+        /// The current permission, either octal or symbolic
+        #[arg(allow_hyphen_values = true)]
+        from: String,
+
+        /// The desired permission, either octal or symbolic
+        #[arg(allow_hyphen_values = true)]
+        to: String,
+
+        /// Don't use ascii color codes, just print raw text
+        #[arg(short, long)]
+        no_color: bool,
+    },
+
+    /// Check whether every bit of the first permission is also set in the
+    /// second one (a ⊆ b). Exits with 1 if not
+    Cmp {
+        /// The permission to check, either octal or symbolic
+        #[arg(allow_hyphen_values = true)]
+        a: String,
+
+        /// The permission to check against, either octal or symbolic
+        #[arg(allow_hyphen_values = true)]
+        b: String,
+    },
+
+    /// List the files under a directory whose permission matches a `find
+    /// -perm` argument
+    Find {
+        /// The directory to search
+        dir: PathBuf,
+
+        /// An octal (`0644`) or symbolic (`g+w`) mode. Prefix it with `-` to
+        /// match files with all of its bits set or with `/` for any of them
+        #[arg(long, value_name = "SPEC", allow_hyphen_values = true)]
+        perm: String,
+    },
+
+    /// Read `git ls-tree` (or `git ls-files -s`) output from stdin and report
+    /// the entries whose working tree mode differs from the recorded one.
+    /// Exits with 1 if any does
+    Git {
+        /// The root of the working tree the paths are relative to
+        #[arg(long, value_name = "DIR", default_value = ".")]
+        root: PathBuf,
+    },
+
+    /// List the entries of a tar archive (ustar, pax or GNU, uncompressed)
+    /// and report their risky permissions, or write a copy with normalized
+    /// modes
+    Tar {
+        /// The archive to read
+        archive: PathBuf,
+
+        /// Exit with 1 if there is a finding of at least this severity
+        /// (`info`, `warning` or `error`)
+        #[arg(long, value_name = "SEVERITY", default_value = "warning")]
+        fail_on: String,
+
+        /// Write a copy of the archive with the modes of directories and
+        /// regular files rewritten to this file
+        #[arg(long, value_name = "FILE", conflicts_with = "fail_on")]
+        normalize: Option<PathBuf>,
+
+        /// The mode of directories in the normalized copy
+        #[arg(
+            long,
+            value_name = "MODE",
+            default_value = "0755",
+            requires = "normalize"
+        )]
+        dir_mode: String,
+
+        /// The mode of regular files in the normalized copy. The default
+        /// keeps the execute bits of executable files
+        #[arg(
+            long,
+            value_name = "MODE",
+            default_value = "u=rwX,go=rX",
+            requires = "normalize"
+        )]
+        file_mode: String,
+    },
+
+    /// List the entries of a zip file in `ls -l` style and report the ones
+    /// without a Unix mode, scripts without execute permission and risky
+    /// permissions
+    Zip {
+        /// The zip file to read
+        archive: PathBuf,
+
+        /// Exit with 1 if there is a finding of at least this severity
+        /// (`info`, `warning` or `error`)
+        #[arg(long, value_name = "SEVERITY", default_value = "warning")]
+        fail_on: String,
+    },
 }
 
 pub fn run_cli() {
     let cli = Cli::parse();
 
-    let permission = FilePermission::try_from(cli.permission.as_str());
+    if let Err(error) = check_pretty(&cli) {
+        error.exit();
+    }
 
-    // if permission is invalid then exit early
-    if let Err(message) = permission {
-        eprintln!("{}", message);
-        std::process::exit(1);
+    match &cli.command {
+        Some(Command::Set {
+            mode,
+            paths,
+            dry_run,
+        }) => {
+            set_permissions(mode, paths, *dry_run);
+            return;
+        }
+        Some(Command::Check {
+            permission,
+            owner,
+            credentials,
+            access,
+        }) => {
+            check_access(permission, owner, credentials, access.as_deref());
+            return;
+        }
+        Some(Command::Lint { targets, fail_on }) => {
+            lint_targets(targets, fail_on);
+            return;
+        }
+        Some(Command::Diff { from, to, no_color }) => {
+            print_diff(from, to, *no_color);
+            return;
+        }
+        Some(Command::Cmp { a, b }) => {
+            compare(a, b);
+            return;
+        }
+        Some(Command::Find { dir, perm }) => {
+            find_files(dir, perm);
+            return;
+        }
+        Some(Command::Git { root }) => {
+            check_git_entries(root);
+            return;
+        }
+        Some(Command::Tar {
+            archive,
+            fail_on,
+            normalize,
+            dir_mode,
+            file_mode,
+        }) => {
+            match normalize {
+                Some(output) => normalize_archive(archive, output, dir_mode, file_mode),
+                None => inspect_archive(archive, fail_on),
+            }
+            return;
+        }
+        Some(Command::Zip { archive, fail_on }) => {
+            inspect_zip(archive, fail_on);
+            return;
+        }
+        None => {}
     }
 
-    let permission = permission.unwrap();
+    if cli.json_schema {
+        print!("{}", JSON_SCHEMA);
+        return;
+    }
 
-    // print json
-    if cli.json {
-        println!("{}", permission.to_json(cli.pretty));
+    if let Some(umask) = &cli.umask {
+        print_umask(umask);
         return;
     }
 
-    // convert to the other format
-    if !cli.analyze {
-        if permission.source_format == Some(SourceFormat::Octal) {
-            println!("{}", permission.to_symbolic_str())
-        } else {
-            println!("{}", permission.to_octal_str())
-        }
+    if !cli.path.is_empty() {
+        print_paths(&cli);
+        return;
+    }
 
+    if cli.ls {
+        annotate_ls_lines(&cli);
         return;
     }
 
@@ -63,6 +422,626 @@ pub fn run_cli() {
         yansi::disable()
     }
 
+    let is_batch = cli.stdin || cli.permissions.len() > 1;
+
+    let renderer = match Renderer::from_cli(&cli, is_batch) {
+        Ok(renderer) => renderer,
+        Err(error) => exit_with_error(&error),
+    };
+
+    if !is_batch {
+        if let Err(error) = print_permission(&cli, &renderer, &cli.permissions[0]) {
+            exit_with_error(&error);
+        }
+
+        return;
+    }
+
+    convert_batch(&cli, &renderer);
+}
+
+/// Converts every permission of the arguments or of stdin, in order, and
+/// reports the ones that fail without stopping.
+fn convert_batch(cli: &Cli, renderer: &Renderer) {
+    let inputs: Vec<(Option<usize>, String)> = if cli.stdin {
+        std::io::stdin()
+            .lines()
+            .enumerate()
+            .map(|(index, line)| match line {
+                Ok(line) => (Some(index + 1), line.trim().to_string()),
+                Err(error) => {
+                    eprintln!("{}", error);
+                    std::process::exit(1);
+                }
+            })
+            .filter(|(_, line)| !line.is_empty())
+            .collect()
+    } else {
+        cli.permissions
+            .iter()
+            .map(|permission| (None, permission.clone()))
+            .collect()
+    };
+
+    let mut failed_count = 0;
+
+    for (index, (line_number, input)) in inputs.iter().enumerate() {
+        // separate the outputs that span several lines
+        match renderer {
+            Renderer::Analysis => {
+                if index > 0 {
+                    println!();
+                }
+
+                println!("{}", input.bold());
+            }
+            Renderer::Format(Format::Table) => {
+                if index > 0 {
+                    println!();
+                }
+
+                println!("{}", input);
+            }
+            Renderer::Format(Format::Yaml) => println!("---"),
+            _ => {}
+        }
+
+        if let Err(error) = print_permission(cli, renderer, input) {
+            failed_count += 1;
+
+            match line_number {
+                Some(line_number) => eprintln!("line {}: {}", line_number, error),
+                None => eprintln!("{}", error),
+            }
+        }
+    }
+
+    if failed_count > 0 {
+        eprintln!("{} of {} permission(s) failed", failed_count, inputs.len());
+        std::process::exit(1);
+    }
+}
+
+/// Parses `input`, applies `--apply` and prints the result.
+fn print_permission(cli: &Cli, renderer: &Renderer, input: &str) -> Result<(), PermconError> {
+    let mut permission = FilePermission::try_from(input)?;
+    let (_, attribute) = LsAttribute::split_mode(input);
+
+    if let Some(expr) = &cli.apply {
+        permission = permission.apply(expr)?;
+    }
+
+    renderer.print(&permission, attribute);
+
+    if attribute == Some(LsAttribute::Acl) && !matches!(renderer, Renderer::Analysis) {
+        eprintln!(
+            "note: \"{}\" has an ACL, so its group bits are the ACL mask",
+            input
+        );
+    }
+
+    Ok(())
+}
+
+fn set_permissions(mode: &str, paths: &[PathBuf], dry_run: bool) {
+    let mode = match ModeSpec::from_str(mode) {
+        Ok(mode) => mode,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let mut changed_count = 0;
+    let mut has_error = false;
+
+    for path in paths {
+        let change = match set_permission(path, &mode, dry_run) {
+            Ok(change) => change,
+            Err(error) => {
+                eprintln!("{}: {}", path.display(), error);
+                has_error = true;
+                continue;
+            }
+        };
+
+        let status = if change.is_changed() {
+            changed_count += 1;
+            ""
+        } else {
+            " (unchanged)"
+        };
+
+        println!(
+            "{}: {} ({}) -> {} ({}){}",
+            path.display(),
+            change.before.to_symbolic_str(),
+            change.before.to_octal_str(),
+            change.after.to_symbolic_str(),
+            change.after.to_octal_str(),
+            status,
+        );
+    }
+
+    let verb = if dry_run { "would change" } else { "changed" };
+    println!("{} of {} file(s) {}", changed_count, paths.len(), verb);
+
+    if has_error {
+        std::process::exit(1);
+    }
+}
+
+fn check_access(permission: &str, owner: &str, credentials: &str, access: Option<&str>) {
+    let parse = || {
+        Ok::<_, PermconError>((
+            FilePermission::try_from(permission)?,
+            Owner::from_str(owner)?,
+            Credentials::from_str(credentials)?,
+            access.map(Access::from_str).transpose()?,
+        ))
+    };
+
+    let (permission, owner, credentials, requested) = match parse() {
+        Ok(parsed) => parsed,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let check = permission.check_access(&owner, &credentials);
+
+    println!("class        : {:?}", check.class);
+    println!("granted      : {}", check.granted);
+
+    if let Some(requested) = requested {
+        let is_allowed = check.granted.allows(&requested);
+        let verdict = if is_allowed { "allowed" } else { "denied" };
+
+        println!("requested    : {} ({})", requested, verdict);
+
+        if !is_allowed {
+            std::process::exit(1);
+        }
+    }
+}
+
+fn lint_targets(targets: &[String], fail_on: &str) {
+    let fail_on = match Severity::from_str(fail_on) {
+        Ok(severity) => severity,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let mut should_fail = false;
+
+    for target in targets {
+        let path = Path::new(target);
+
+        let findings = match FilePermission::try_from(target.as_str()) {
+            Ok(permission) => lint(&permission, None),
+            Err(_) if path.exists() => match FilePermission::from_path_follow_symlinks(path) {
+                Ok(permission) => lint(&permission, Some(path)),
+                Err(error) => {
+                    eprintln!("{}: {}", target, error);
+                    should_fail = true;
+                    continue;
+                }
+            },
+            Err(error) => {
+                eprintln!("{}", error);
+                should_fail = true;
+                continue;
+            }
+        };
+
+        for finding in findings {
+            should_fail |= finding.severity >= fail_on;
+
+            println!(
+                "{}: {} [{}] {}",
+                target, finding.severity, finding.id, finding.explanation
+            );
+        }
+    }
+
+    if should_fail {
+        std::process::exit(1);
+    }
+}
+
+fn print_diff(from: &str, to: &str, no_color: bool) {
+    let parse = || {
+        Ok::<_, PermconError>((
+            FilePermission::try_from(from)?,
+            FilePermission::try_from(to)?,
+        ))
+    };
+
+    let (from, to) = match parse() {
+        Ok(parsed) => parsed,
+        Err(error) => exit_with_error(&error),
+    };
+
+    if no_color {
+        yansi::disable()
+    }
+
+    let diff = from.diff(&to);
+    let (from_str, to_str) = (from.to_symbolic_str(), to.to_symbolic_str());
+
+    // highlight the positions that differ, the file type is never compared
+    let highlight = |symbolic: &str, other: &str, is_removal: bool| {
+        symbolic
+            .chars()
+            .zip(other.chars())
+            .enumerate()
+            .map(|(index, (ch, other_ch))| {
+                if index == 0 || ch == other_ch {
+                    ch.to_string()
+                } else if is_removal {
+                    ch.red().bold().to_string()
+                } else {
+                    ch.green().bold().to_string()
+                }
+            })
+            .collect::<String>()
+    };
+
+    println!(
+        "from         : {} ({})",
+        highlight(&from_str, &to_str, true),
+        from.to_octal_str()
+    );
+    println!(
+        "to           : {} ({})",
+        highlight(&to_str, &from_str, false),
+        to.to_octal_str()
+    );
+
+    if diff.is_empty() {
+        println!("chmod        : (unchanged)");
+    } else {
+        println!("chmod        : {}", diff.to_chmod_expr().yellow());
+    }
+}
+
+fn compare(a: &str, b: &str) {
+    let parse =
+        || Ok::<_, PermconError>((FilePermission::try_from(a)?, FilePermission::try_from(b)?));
+
+    let (a, b) = match parse() {
+        Ok(parsed) => parsed,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let is_subset = a.is_subset(&b);
+
+    println!(
+        "a            : {} ({})",
+        a.to_symbolic_str(),
+        a.to_octal_str()
+    );
+    println!(
+        "b            : {} ({})",
+        b.to_symbolic_str(),
+        b.to_octal_str()
+    );
+    println!("a ⊆ b        : {}", if is_subset { "yes" } else { "no" });
+
+    if !is_subset {
+        let extra = a.difference(&b);
+        println!(
+            "only in a    : {} ({})",
+            extra.to_symbolic_str(),
+            extra.to_octal_str()
+        );
+        std::process::exit(1);
+    }
+}
+
+fn find_files(dir: &Path, perm: &str) {
+    let matcher = match PermMatcher::from_str(perm) {
+        Ok(matcher) => matcher,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let mut has_error = false;
+
+    find_permissions(dir, &matcher, |result| match result {
+        Ok((path, permission)) => println!(
+            "{} {} {}",
+            permission.to_symbolic_str(),
+            permission.to_octal_str(),
+            path.display()
+        ),
+        Err(error) => {
+            eprintln!("{}", error);
+            has_error = true;
+        }
+    });
+
+    if has_error {
+        std::process::exit(1);
+    }
+}
+
+fn check_git_entries(root: &Path) {
+    let mut has_error = false;
+
+    for line in std::io::stdin().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        };
+
+        let entry = match GitEntry::from_str(&line) {
+            Ok(entry) => entry,
+            Err(error) => {
+                eprintln!("{}", error);
+                has_error = true;
+                continue;
+            }
+        };
+
+        println!(
+            "{} {:<15} {}",
+            entry.mode,
+            entry.mode.description(),
+            entry.path
+        );
+
+        // entries that aren't checked out have nothing to compare with
+        let worktree = match FilePermission::from_path(root.join(&entry.path)) {
+            Ok(worktree) => worktree,
+            Err(error) => {
+                println!("    ! working tree: {}", error);
+                continue;
+            }
+        };
+
+        if !entry.matches_worktree(&worktree) {
+            let worktree_mode = worktree
+                .to_git_mode()
+                .map_or(String::from("untracked type"), |mode| mode.to_string());
+
+            println!(
+                "    ! working tree: {} ({})",
+                worktree_mode,
+                worktree.to_symbolic_str()
+            );
+            has_error = true;
+        }
+
+        if let Some(dropped) = worktree.git_dropped_bits() {
+            let diff = worktree.diff(&worktree.difference(&dropped));
+
+            if !diff.is_empty() {
+                println!(
+                    "    ! dropped by git: {} ({})",
+                    diff.to_chmod_expr(),
+                    worktree.to_symbolic_str()
+                );
+            }
+        }
+    }
+
+    if has_error {
+        std::process::exit(1);
+    }
+}
+
+fn inspect_archive(archive: &Path, fail_on: &str) {
+    let fail_on = match Severity::from_str(fail_on) {
+        Ok(severity) => severity,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let entries = match File::open(archive).and_then(read_tar_entries) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("{}: {}", archive.display(), error);
+            std::process::exit(1);
+        }
+    };
+
+    let mut should_fail = false;
+
+    for entry in entries {
+        let target = match &entry.link_target {
+            Some(target) => format!(" -> {}", target),
+            None => String::new(),
+        };
+
+        println!(
+            "{} {} {}/{} {}{}",
+            entry.permission.to_symbolic_str(),
+            entry.permission.to_octal_str(),
+            entry.owner,
+            entry.group,
+            entry.path,
+            target
+        );
+
+        for finding in lint_archive_entry(&entry.permission, Path::new(&entry.path)) {
+            should_fail |= finding.severity >= fail_on;
+
+            println!(
+                "    {} [{}] {}",
+                finding.severity, finding.id, finding.explanation
+            );
+        }
+    }
+
+    if should_fail {
+        std::process::exit(1);
+    }
+}
+
+fn inspect_zip(archive: &Path, fail_on: &str) {
+    let fail_on = match Severity::from_str(fail_on) {
+        Ok(severity) => severity,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let entries = match File::open(archive).and_then(read_zip_entries) {
+        Ok(entries) => entries,
+        Err(error) => {
+            eprintln!("{}: {}", archive.display(), error);
+            std::process::exit(1);
+        }
+    };
+
+    let mut should_fail = false;
+
+    for entry in entries {
+        // like `ls -l` for a file it can't stat, only the file type is known
+        let symbolic = match &entry.permission {
+            Some(permission) => permission.to_symbolic_str(),
+            None if entry.is_directory() => format!("{}?????????", FileType::Directory.to_char()),
+            None => format!("{}?????????", FileType::Regular.to_char()),
+        };
+
+        println!(
+            "{} {:>8} {} {}",
+            symbolic, entry.size, entry.modified, entry.path
+        );
+
+        for finding in lint_zip_entry(&entry) {
+            should_fail |= finding.severity >= fail_on;
+
+            println!(
+                "    {} [{}] {}",
+                finding.severity, finding.id, finding.explanation
+            );
+        }
+    }
+
+    if should_fail {
+        std::process::exit(1);
+    }
+}
+
+fn normalize_archive(archive: &Path, output: &Path, dir_mode: &str, file_mode: &str) {
+    let parse = || {
+        Ok::<_, PermconError>(TarNormalizeOptions {
+            directory_mode: Some(ModeSpec::from_str(dir_mode)?),
+            file_mode: Some(ModeSpec::from_str(file_mode)?),
+        })
+    };
+
+    let options = match parse() {
+        Ok(options) => options,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let normalize = || {
+        let reader = File::open(archive)?;
+        let writer = BufWriter::new(File::create(output)?);
+
+        normalize_tar(reader, writer, &options)
+    };
+
+    let changes = match normalize() {
+        Ok(changes) => changes,
+        Err(error) => {
+            eprintln!("{}: {}", archive.display(), error);
+            std::process::exit(1);
+        }
+    };
+
+    let mut changed_count = 0;
+
+    for change in &changes {
+        let status = if change.is_changed() {
+            changed_count += 1;
+            ""
+        } else {
+            " (unchanged)"
+        };
+
+        println!(
+            "{}: {} ({}) -> {} ({}){}",
+            change.path.display(),
+            change.before.to_symbolic_str(),
+            change.before.to_octal_str(),
+            change.after.to_symbolic_str(),
+            change.after.to_octal_str(),
+            status,
+        );
+    }
+
+    println!(
+        "{} of {} entries changed, written to {}",
+        changed_count,
+        changes.len(),
+        output.display()
+    );
+}
+
+fn print_paths(cli: &Cli) {
+    if cli.no_color {
+        yansi::disable()
+    }
+
+    let mut has_error = false;
+
+    for (index, path) in cli.path.iter().enumerate() {
+        let permission = match FilePermission::from_path(path) {
+            Ok(permission) => permission,
+            Err(error) => {
+                eprintln!("{}: {}", path.display(), error);
+                has_error = true;
+                continue;
+            }
+        };
+
+        if cli.json {
+            println!("{}", permission.to_json(cli.pretty));
+        } else if cli.analyze {
+            if index > 0 {
+                println!();
+            }
+
+            println!("{}", path.display().bold());
+            print_analysis(&permission, None);
+        } else {
+            println!(
+                "{} {} {}",
+                permission.to_symbolic_str(),
+                permission.to_octal_str(),
+                path.display()
+            );
+        }
+    }
+
+    if has_error {
+        std::process::exit(1);
+    }
+}
+
+fn annotate_ls_lines(cli: &Cli) {
+    for line in std::io::stdin().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        };
+
+        let entry = LsEntry::from_str(&line);
+
+        if cli.json {
+            // only the entries, lines like `total 8` are skipped
+            if let Ok(entry) = entry {
+                println!("{}", entry.to_json(cli.pretty));
+            }
+        } else if let Ok(entry) = entry {
+            println!("{} {}", entry.permission.to_octal_str(), line);
+        } else {
+            println!("{:4} {}", "", line);
+        }
+    }
+}
+
+fn print_analysis(permission: &FilePermission, attribute: Option<LsAttribute>) {
     let [user_bits, group_bits, other_bits] = permission.to_symbolic_bits_arr();
     let [user_digit, group_digit, other_digit] = permission
         .to_perm_group_array()
@@ -71,6 +1050,16 @@ pub fn run_cli() {
     let [suid, sgid, sticky_bit] = &permission.special;
 
     println!("file type    : {}", permission.filetype);
+
+    match attribute {
+        Some(LsAttribute::Acl) => println!(
+            "attribute    : {}, the group bits are the ACL mask",
+            LsAttribute::Acl
+        ),
+        Some(attribute) => println!("attribute    : {}", attribute),
+        None => {}
+    }
+
     println!("symbolic     : {}", permission.to_symbolic_str().green());
     println!("octal        : {}", permission.to_octal_str().yellow());
     println!("------------------------");
@@ -121,6 +1110,42 @@ pub fn run_cli() {
     println!("{}: {}", "special permissions".green(), special_perm_str)
 }
 
+fn print_umask(umask: &str) {
+    let umask = match Umask::from_str(umask) {
+        Ok(umask) => umask,
+        Err(error) => exit_with_error(&error),
+    };
+
+    let file = umask.file_permission();
+    let directory = umask.directory_permission();
+
+    println!("umask        : {} ({})", umask, umask.to_symbolic_str());
+    println!(
+        "file         : {} ({})",
+        file.to_symbolic_str(),
+        file.to_octal_str()
+    );
+    println!(
+        "directory    : {} ({})",
+        directory.to_symbolic_str(),
+        directory.to_octal_str()
+    );
+}
+
+/// Prints the error and, if it points at a char, the input with a caret
+/// under that char. Then exits with status 1.
+fn exit_with_error(error: &PermconError) -> ! {
+    eprintln!("{}", error);
+
+    if let Some(position) = error.position() {
+        eprintln!();
+        eprintln!("    {}", error.input());
+        eprintln!("    {}^", " ".repeat(position));
+    }
+
+    std::process::exit(1);
+}
+
 fn get_perm_description(perm: &GroupPermission, special: &SpecialPermission) -> String {
     let mut desc = String::new();
 
@@ -157,5 +1182,48 @@ fn get_perm_description(perm: &GroupPermission, special: &SpecialPermission) ->
 
     desc.push_str(format!("({}, {})", execute_str, special_str).as_str());
 
-    return desc;
+    desc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["permcon"], args].concat()).unwrap()
+    }
+
+    #[test]
+    fn test_pretty_json() {
+        for args in [
+            &["0644", "--json", "--pretty"][..],
+            &["0644", "--to", "json", "--pretty"],
+        ] {
+            let cli = parse(args);
+
+            assert!(check_pretty(&cli).is_ok(), "{args:?}");
+            assert!(matches!(
+                Renderer::from_cli(&cli, false),
+                Ok(Renderer::Json { pretty: true })
+            ));
+        }
+
+        // JSON Lines in a batch
+        let cli = parse(&["0644", "0755", "--to", "json", "--pretty"]);
+        assert!(matches!(
+            Renderer::from_cli(&cli, true),
+            Ok(Renderer::Json { pretty: false })
+        ));
+
+        for args in [
+            &["0644", "--pretty"][..],
+            &["0644", "--to", "octal", "--pretty"],
+        ] {
+            assert_eq!(
+                check_pretty(&parse(args)).map_err(|error| error.kind()),
+                Err(ErrorKind::MissingRequiredArgument),
+                "{args:?}"
+            );
+        }
+    }
 }
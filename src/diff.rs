@@ -0,0 +1,192 @@
+use crate::{
+    filetype::FileType,
+    perm::{FilePermission, GroupPermission},
+};
+
+/// The bits of one class (user, group or other) that were added and removed.
+/// The `special` flag stands for the special bit of the class: set-user-ID
+/// for the user, set-group-ID for the group and the sticky bit for others.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ClassDiff {
+    pub added: GroupPermission,
+    pub removed: GroupPermission,
+}
+
+/// The difference between two permissions, see [`FilePermission::diff`].
+/// The file types are not compared.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PermissionDiff {
+    pub user: ClassDiff,
+    pub group: ClassDiff,
+    pub other: ClassDiff,
+}
+
+impl FilePermission {
+    /// Compares `self` to `other` and returns the bits `other` adds and
+    /// removes.
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let from = FilePermission::try_from("0755").unwrap();
+    /// let to = FilePermission::try_from("4750").unwrap();
+    /// let diff = from.diff(&to);
+    ///
+    /// assert!(diff.user.added.special);
+    /// assert!(diff.other.removed.read && diff.other.removed.execute);
+    /// assert_eq!(diff.to_chmod_expr(), "u+s,o-rx");
+    /// assert_eq!(from.apply(&diff.to_chmod_expr()).unwrap().to_octal_str(), "4750");
+    /// ```
+    pub fn diff(&self, other: &FilePermission) -> PermissionDiff {
+        let (from, to) = (self.to_bits(), other.to_bits());
+
+        let added = FilePermission::from_bits(to & !from, FileType::Unknown);
+        let removed = FilePermission::from_bits(from & !to, FileType::Unknown);
+
+        PermissionDiff {
+            user: ClassDiff {
+                added: added.user,
+                removed: removed.user,
+            },
+            group: ClassDiff {
+                added: added.group,
+                removed: removed.group,
+            },
+            other: ClassDiff {
+                added: added.other,
+                removed: removed.other,
+            },
+        }
+    }
+}
+
+impl PermissionDiff {
+    /// Returns `[user, group, other]`.
+    pub fn to_class_diff_array(&self) -> [&ClassDiff; 3] {
+        [&self.user, &self.group, &self.other]
+    }
+
+    /// Returns `true` if both permissions have the same bits.
+    pub fn is_empty(&self) -> bool {
+        let unchanged = GroupPermission::from_octal_digit(0, false).unwrap();
+
+        self.to_class_diff_array()
+            .iter()
+            .all(|class| class.added == unchanged && class.removed == unchanged)
+    }
+
+    /// Renders the chmod expression (e.g., `u+s,o-rx`) that turns the first
+    /// permission into the second. Classes with the same changes share a
+    /// clause (e.g., `go-w`). Returns an empty string if nothing changed.
+    pub fn to_chmod_expr(&self) -> String {
+        // the changes of every class as (who, "+perms", "-perms")
+        let changes: Vec<(char, String, String)> = ['u', 'g', 'o']
+            .into_iter()
+            .zip(self.to_class_diff_array())
+            .zip(['s', 's', 't'])
+            .map(|((who, class), special_char)| {
+                (
+                    who,
+                    to_perms_str(&class.added, special_char),
+                    to_perms_str(&class.removed, special_char),
+                )
+            })
+            .collect();
+
+        let mut clauses: Vec<(String, &str, &str)> = Vec::new();
+
+        for (who, added, removed) in &changes {
+            if added.is_empty() && removed.is_empty() {
+                continue;
+            }
+
+            match clauses
+                .iter_mut()
+                .find(|(_, clause_added, clause_removed)| {
+                    clause_added == added && clause_removed == removed
+                }) {
+                Some((clause_who, _, _)) => clause_who.push(*who),
+                None => clauses.push((who.to_string(), added, removed)),
+            }
+        }
+
+        clauses
+            .into_iter()
+            .map(|(who, added, removed)| {
+                // `a` instead of no class at all, which would honour the umask
+                let who = if who == "ugo" { String::from("a") } else { who };
+                let mut clause = who;
+
+                for (op, perms) in [('+', added), ('-', removed)] {
+                    if !perms.is_empty() {
+                        clause.push(op);
+                        clause.push_str(perms);
+                    }
+                }
+
+                clause
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+/// Returns the chmod permission symbols (e.g., `rxs`) of a group permission.
+fn to_perms_str(perm: &GroupPermission, special_char: char) -> String {
+    ['r', 'w', 'x', special_char]
+        .into_iter()
+        .zip([perm.read, perm.write, perm.execute, perm.special])
+        .filter(|(_, is_set)| *is_set)
+        .map(|(char, _)| char)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chmod_expr() {
+        let test_cases: [(&str, &str, &str); 8] = [
+            ("0755", "4750", "u+s,o-rx"),
+            ("0644", "0644", ""),
+            ("0666", "0644", "go-w"),
+            ("0000", "0777", "a+rwx"),
+            ("0755", "0644", "a-x"),
+            ("2775", "1755", "g-ws,o+t"),
+            ("0640", "0604", "g-r,o+r"),
+            ("6755", "0755", "ug-s"),
+        ];
+
+        for (from, to, expr) in test_cases {
+            let from = FilePermission::try_from(from).unwrap();
+            let to = FilePermission::try_from(to).unwrap();
+            let diff = from.diff(&to);
+
+            assert_eq!(diff.to_chmod_expr(), expr, "{from:?} -> {to:?}");
+            assert_eq!(diff.is_empty(), expr.is_empty());
+
+            if !expr.is_empty() {
+                assert_eq!(from.apply(expr).unwrap().to_octal_str(), to.to_octal_str());
+            }
+        }
+    }
+
+    #[test]
+    fn test_diff_round_trips_all_modes() {
+        let from = FilePermission::try_from("2751").unwrap();
+
+        for bits in 0..=0o7777u16 {
+            let to = FilePermission::from_bits(bits, FileType::Unknown);
+            let expr = from.diff(&to).to_chmod_expr();
+
+            let result = if expr.is_empty() {
+                from.clone()
+            } else {
+                from.apply(&expr).unwrap()
+            };
+
+            assert_eq!(result.to_bits(), bits, "{expr}");
+        }
+    }
+}
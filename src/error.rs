@@ -0,0 +1,184 @@
+use std::{error, fmt};
+
+/// The error returned by every fallible parser of this crate. Positions are
+/// zero based char indices into `input`.
+///
+/// ```rust
+/// use permcon::{FilePermission, PermconError};
+///
+/// let error = FilePermission::try_from("rwxr-xr-z").unwrap_err();
+///
+/// assert_eq!(error, PermconError::InvalidChar {
+///     input: String::from("rwxr-xr-z"),
+///     position: 8,
+///     found: 'z',
+///     expected: String::from("one of 'x', 't', 'T', '-'"),
+/// });
+/// assert_eq!(error.position(), Some(8));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum PermconError {
+    /// The input doesn't have any of the `expected` lengths.
+    InvalidLength {
+        input: String,
+        expected: Vec<usize>,
+        found: usize,
+    },
+
+    /// The char at `position` is not allowed there.
+    InvalidChar {
+        input: String,
+        position: usize,
+        found: char,
+        expected: String,
+    },
+
+    /// An octal digit is not within `0..=7`.
+    OctalDigitOutOfRange {
+        input: String,
+        position: usize,
+        digit: u8,
+    },
+
+    /// The file type char of a symbolic permission is unknown.
+    UnknownFileType {
+        input: String,
+        position: usize,
+        found: char,
+    },
+
+    /// A chmod clause ends without an operator (`+`, `-` or `=`).
+    MissingOperator { input: String, position: usize },
+
+    /// The input ends while `expected` is still missing.
+    UnexpectedEnd { input: String, expected: String },
+
+    /// A umask has bits outside of `0777`.
+    UmaskOutOfRange { input: String },
+
+    /// The columns after the mode of an `ls -l` line are malformed.
+    InvalidLsLine { input: String },
+
+    /// A line is neither a `git ls-tree` nor a `git ls-files -s` entry.
+    InvalidGitLine { input: String },
+
+    /// An ACL lacks a required `entry` (e.g., `user::`).
+    MissingAclEntry { input: String, entry: String },
+
+    /// The input is not one of the accepted keywords.
+    InvalidValue { input: String, expected: String },
+}
+
+impl PermconError {
+    /// Returns the input that failed to parse.
+    pub fn input(&self) -> &str {
+        match self {
+            PermconError::InvalidLength { input, .. }
+            | PermconError::InvalidChar { input, .. }
+            | PermconError::OctalDigitOutOfRange { input, .. }
+            | PermconError::UnknownFileType { input, .. }
+            | PermconError::MissingOperator { input, .. }
+            | PermconError::UnexpectedEnd { input, .. }
+            | PermconError::UmaskOutOfRange { input }
+            | PermconError::InvalidLsLine { input }
+            | PermconError::InvalidGitLine { input }
+            | PermconError::MissingAclEntry { input, .. }
+            | PermconError::InvalidValue { input, .. } => input,
+        }
+    }
+
+    /// Returns the position of the offending char, if the error points at one.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            PermconError::InvalidChar { position, .. }
+            | PermconError::OctalDigitOutOfRange { position, .. }
+            | PermconError::UnknownFileType { position, .. }
+            | PermconError::MissingOperator { position, .. } => Some(*position),
+            PermconError::UnexpectedEnd { input, .. } => Some(input.chars().count()),
+            PermconError::InvalidLength { .. }
+            | PermconError::UmaskOutOfRange { .. }
+            | PermconError::InvalidLsLine { .. }
+            | PermconError::InvalidGitLine { .. }
+            | PermconError::MissingAclEntry { .. }
+            | PermconError::InvalidValue { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for PermconError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PermconError::InvalidLength {
+                input,
+                expected,
+                found,
+            } => {
+                let mut expected: Vec<String> =
+                    expected.iter().map(|length| length.to_string()).collect();
+                let last = expected.pop().unwrap_or_default();
+
+                let expected = if expected.is_empty() {
+                    last
+                } else {
+                    format!("{} or {last}", expected.join(", "))
+                };
+
+                write!(
+                    f,
+                    "Invalid length of \"{input}\": expected {expected} chars, found {found}!"
+                )
+            }
+            PermconError::InvalidChar {
+                input,
+                position,
+                found,
+                expected,
+            } => write!(
+                f,
+                "Invalid char '{found}' at position {position} of \"{input}\": expected {expected}!"
+            ),
+            PermconError::OctalDigitOutOfRange {
+                input,
+                position,
+                digit,
+            } => write!(
+                f,
+                "Invalid octal digit {digit} at position {position} of \"{input}\": it must be within 0..=7!"
+            ),
+            PermconError::UnknownFileType {
+                input,
+                position,
+                found,
+            } => write!(
+                f,
+                "Unknown file type '{found}' at position {position} of \"{input}\": expected one of 'b', 'c', 'd', 'l', 'p', 's', 'D', 'w', '-'!"
+            ),
+            PermconError::MissingOperator { input, position } => write!(
+                f,
+                "Missing operator at position {position} of \"{input}\": expected one of '+', '-', '='!"
+            ),
+            PermconError::UnexpectedEnd { input, expected } => {
+                write!(f, "Unexpected end of \"{input}\": expected {expected}!")
+            }
+            PermconError::UmaskOutOfRange { input } => {
+                write!(f, "Invalid umask: {input}! It must be within 0000..=0777.")
+            }
+            PermconError::InvalidLsLine { input } => write!(
+                f,
+                "Invalid `ls -l` line \"{input}\": expected the link count, owner, group, size, date and name after the mode!"
+            ),
+            PermconError::InvalidGitLine { input } => write!(
+                f,
+                "Invalid git entry \"{input}\": expected `<mode> <type> <object>\\t<path>` or `<mode> <object> <stage>\\t<path>`!"
+            ),
+            PermconError::MissingAclEntry { entry, .. } => {
+                write!(f, "Invalid ACL: the required \"{entry}\" entry is missing!")
+            }
+            PermconError::InvalidValue { input, expected } => {
+                write!(f, "Invalid value \"{input}\": expected {expected}!")
+            }
+        }
+    }
+}
+
+impl error::Error for PermconError {}
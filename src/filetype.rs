@@ -0,0 +1,180 @@
+use crate::{error::PermconError, mode::Mode};
+use std::{fmt, str::FromStr};
+
+/// The type of a file, as shown by the first char of `ls -l` and stored in
+/// the `S_IFMT` bits of `st_mode`.
+///
+/// ```rust
+/// use permcon::FileType;
+///
+/// assert_eq!(FileType::from_char('d'), FileType::Directory);
+/// assert_eq!(FileType::from_mode(0o120777), FileType::Symlink);
+/// assert_eq!(FileType::Fifo.to_char(), 'p');
+/// assert_eq!(FileType::CharDevice.to_string(), "Character Device");
+///
+/// // a permission parsed from octal notation has no file type, shown as `-`
+/// let perm = permcon::FilePermission::try_from("0755").unwrap();
+/// assert_eq!(perm.filetype, FileType::Unknown);
+/// assert_eq!(perm.filetype.to_char(), '-');
+/// assert_eq!(perm.to_symbolic_str(), "-rwxr-xr-x");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum FileType {
+    Regular,
+    Directory,
+    Symlink,
+    BlockDevice,
+    CharDevice,
+    Socket,
+    /// A named pipe.
+    Fifo,
+    /// A Solaris door.
+    Door,
+    /// A BSD whiteout.
+    Whiteout,
+    /// The file type isn't known, e.g., of a permission parsed from octal
+    /// notation.
+    Unknown,
+}
+
+impl FileType {
+    /// All the file types.
+    pub const ALL: [FileType; 10] = [
+        FileType::Regular,
+        FileType::Directory,
+        FileType::Symlink,
+        FileType::BlockDevice,
+        FileType::CharDevice,
+        FileType::Socket,
+        FileType::Fifo,
+        FileType::Door,
+        FileType::Whiteout,
+        FileType::Unknown,
+    ];
+
+    /// Returns the file type of an `ls -l` file type char. Unrecognized chars
+    /// (e.g., the `?` of `ls`) map to `Unknown`.
+    pub const fn from_char(filetype_char: char) -> Self {
+        match filetype_char {
+            '-' => FileType::Regular,
+            'd' => FileType::Directory,
+            'l' => FileType::Symlink,
+            'b' => FileType::BlockDevice,
+            'c' => FileType::CharDevice,
+            's' => FileType::Socket,
+            'p' => FileType::Fifo,
+            'D' => FileType::Door,
+            'w' => FileType::Whiteout,
+            _ => FileType::Unknown,
+        }
+    }
+
+    /// Returns the `ls -l` file type char. `Unknown` has no char of its own
+    /// and is shown as `-`, like a bare permission in `stat`'s `%A`.
+    pub const fn to_char(self) -> char {
+        match self {
+            FileType::Regular | FileType::Unknown => '-',
+            FileType::Directory => 'd',
+            FileType::Symlink => 'l',
+            FileType::BlockDevice => 'b',
+            FileType::CharDevice => 'c',
+            FileType::Socket => 's',
+            FileType::Fifo => 'p',
+            FileType::Door => 'D',
+            FileType::Whiteout => 'w',
+        }
+    }
+
+    /// Returns the file type of the `S_IFMT` bits of an `st_mode` (e.g.,
+    /// `0o100644`). Missing or unrecognized bits map to `Unknown`.
+    pub const fn from_mode(mode: u32) -> Self {
+        match mode & Mode::S_IFMT.bits() {
+            0o140000 => FileType::Socket,
+            0o120000 => FileType::Symlink,
+            0o100000 => FileType::Regular,
+            0o060000 => FileType::BlockDevice,
+            0o040000 => FileType::Directory,
+            0o020000 => FileType::CharDevice,
+            0o010000 => FileType::Fifo,
+            0o150000 => FileType::Door,
+            0o160000 => FileType::Whiteout,
+            _ => FileType::Unknown,
+        }
+    }
+
+    /// Returns the `S_IFMT` bits of the file type, `0` for `Unknown`.
+    pub const fn to_mode_bits(self) -> u32 {
+        match self {
+            FileType::Socket => 0o140000,
+            FileType::Symlink => 0o120000,
+            FileType::Regular => 0o100000,
+            FileType::BlockDevice => 0o060000,
+            FileType::Directory => 0o040000,
+            FileType::CharDevice => 0o020000,
+            FileType::Fifo => 0o010000,
+            FileType::Door => 0o150000,
+            FileType::Whiteout => 0o160000,
+            FileType::Unknown => 0,
+        }
+    }
+
+    /// Returns the human-readable name (e.g., `Named Pipe`).
+    pub const fn name(self) -> &'static str {
+        match self {
+            FileType::Regular => "Regular File",
+            FileType::Directory => "Directory",
+            FileType::Symlink => "Symbolic Link",
+            FileType::BlockDevice => "Block Device",
+            FileType::CharDevice => "Character Device",
+            FileType::Socket => "Socket",
+            FileType::Fifo => "Named Pipe",
+            FileType::Door => "Door",
+            FileType::Whiteout => "Whiteout",
+            FileType::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for FileType {
+    /// Formats the human-readable name, see [`FileType::name`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for FileType {
+    type Err = PermconError;
+
+    /// Tries to parse a human-readable name (e.g., `Directory`).
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        FileType::ALL
+            .into_iter()
+            .find(|filetype| filetype.name() == name)
+            .ok_or_else(|| PermconError::InvalidValue {
+                input: name.to_string(),
+                expected: String::from("the name of a file type (e.g., 'Regular File')"),
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conversions() {
+        for filetype in FileType::ALL {
+            assert_eq!(FileType::from_str(&filetype.to_string()), Ok(filetype));
+            assert_eq!(FileType::from_mode(filetype.to_mode_bits()), filetype);
+
+            if filetype != FileType::Unknown {
+                assert_eq!(FileType::from_char(filetype.to_char()), filetype);
+            }
+        }
+
+        assert_eq!(FileType::from_char('?'), FileType::Unknown);
+        assert_eq!(FileType::Unknown.to_char(), '-');
+        assert_eq!(FileType::from_mode(0o170644), FileType::Unknown);
+        assert!(FileType::from_str("File").is_err());
+    }
+}
@@ -0,0 +1,182 @@
+use crate::{chmod::ChmodExpr, error::PermconError, filetype::FileType, perm::FilePermission};
+use std::str::FromStr;
+
+/// How the permission bits of a file are compared to the mode of a
+/// [`PermMatcher`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MatchKind {
+    /// `mode`: the bits are exactly the mode.
+    Exact,
+    /// `-mode`: all the bits of the mode are set.
+    All,
+    /// `/mode`: any of the bits of the mode is set.
+    Any,
+}
+
+/// Represents a parsed `-perm` argument of `find`: an octal (`0644`) or
+/// symbolic (`g+w,o+w`) mode with an optional `-` or `/` prefix.
+///
+/// A symbolic mode is applied to `000` and `X` only counts for directories,
+/// like GNU find does.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::find::PermMatcher;
+/// use permcon::FilePermission;
+///
+/// let world_writable = PermMatcher::from_str("-0002").unwrap();
+/// let setid = PermMatcher::from_str("/ug+s").unwrap();
+///
+/// let perm = FilePermission::try_from("-rwsr-xr-x").unwrap();
+///
+/// assert!(!world_writable.matches(&perm));
+/// assert!(setid.matches(&perm));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct PermMatcher {
+    pub kind: MatchKind,
+    /// The mode compared to the permission of non-directories.
+    pub file_bits: u16,
+    /// The mode compared to the permission of directories.
+    pub directory_bits: u16,
+}
+
+impl PermMatcher {
+    /// Tests the permission and special bits of `permission`. An any-of
+    /// match of `/000` matches every file, just like the all-of match of
+    /// `-000`.
+    pub fn matches(&self, permission: &FilePermission) -> bool {
+        let bits = if permission.filetype == FileType::Directory {
+            self.directory_bits
+        } else {
+            self.file_bits
+        };
+        let mode = permission.to_bits();
+
+        match self.kind {
+            MatchKind::Exact => mode == bits,
+            MatchKind::All => mode & bits == bits,
+            MatchKind::Any => bits == 0 || mode & bits != 0,
+        }
+    }
+}
+
+impl FromStr for PermMatcher {
+    type Err = PermconError;
+
+    /// Tries to parse `[-|/]mode` where `mode` is an octal number up to
+    /// `7777` or a chmod expression. Errors of a chmod expression refer to
+    /// the mode without the prefix.
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let (kind, mode) = match spec.chars().next() {
+            Some('-') => (MatchKind::All, &spec[1..]),
+            Some('/') => (MatchKind::Any, &spec[1..]),
+            _ => (MatchKind::Exact, spec),
+        };
+        let offset = spec.len() - mode.len();
+
+        if mode.is_empty() {
+            return Err(PermconError::UnexpectedEnd {
+                input: spec.to_string(),
+                expected: String::from("an octal or symbolic mode"),
+            });
+        }
+
+        if !mode.chars().all(|ch| ch.is_ascii_digit()) {
+            let expr = ChmodExpr::from_str(mode)?;
+
+            return Ok(PermMatcher {
+                kind,
+                file_bits: expr.apply_to_bits(0, false),
+                directory_bits: expr.apply_to_bits(0, true),
+            });
+        }
+
+        let mut bits: u32 = 0;
+
+        for (index, ch) in mode.chars().enumerate() {
+            let digit = ch.to_digit(10).unwrap();
+
+            if digit > 7 {
+                return Err(PermconError::OctalDigitOutOfRange {
+                    input: spec.to_string(),
+                    position: offset + index,
+                    digit: digit as u8,
+                });
+            }
+
+            bits = (bits * 8 + digit).min(0o10000);
+        }
+
+        if bits > 0o7777 {
+            return Err(PermconError::InvalidValue {
+                input: spec.to_string(),
+                expected: String::from("an octal mode within 0..=7777"),
+            });
+        }
+
+        Ok(PermMatcher {
+            kind,
+            file_bits: bits as u16,
+            directory_bits: bits as u16,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        // (spec, permission, is_match)
+        let test_cases: [(&str, &str, bool); 16] = [
+            ("644", "-rw-r--r--", true),
+            ("0644", "-rwxr--r--", false),
+            ("u=rw,go=r", "-rw-r--r--", true),
+            ("-0002", "drwxrwxrwt", true),
+            ("-0002", "-rw-r--r--", false),
+            ("-g+w,o+w", "-rw-rw-r--", false),
+            ("-g+w,o+w", "-rw-rw-rw-", true),
+            ("/6000", "-rwxr-sr-x", true),
+            ("/6000", "drwxrwxrwt", false),
+            ("/ug+s", "-rwsr-xr-x", true),
+            // `/000` and `-000` match everything
+            ("/000", "----------", true),
+            ("-000", "----------", true),
+            ("000", "-------r--", false),
+            // `X` only counts for directories
+            ("-a+X", "drwxr-xr-x", true),
+            ("-a+X", "-rw-r--r--", true),
+            ("a+X", "-rw-r--r--", false),
+        ];
+
+        for (spec, permission, is_match) in test_cases {
+            let matcher = PermMatcher::from_str(spec).unwrap();
+            let permission = FilePermission::try_from(permission).unwrap();
+
+            assert_eq!(
+                matcher.matches(&permission),
+                is_match,
+                "{spec} {permission:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_specs() {
+        // (spec, error position)
+        let test_cases: [(&str, Option<usize>); 5] = [
+            ("-", Some(1)),
+            ("/0682", Some(3)),
+            ("17777", None),
+            ("-u+z", Some(2)),
+            ("+0644", Some(1)),
+        ];
+
+        for (spec, position) in test_cases {
+            let error = PermMatcher::from_str(spec).unwrap_err();
+            assert_eq!(error.position(), position, "{spec}");
+        }
+    }
+}
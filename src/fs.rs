@@ -0,0 +1,677 @@
+use crate::{
+    chmod::ChmodExpr, error::PermconError, filetype::FileType, find::PermMatcher, mode::Mode,
+    perm::FilePermission,
+};
+use std::{
+    collections::HashSet,
+    error, fmt, fs, io,
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The new mode to set on a file: either an absolute permission (`0755`,
+/// `rwxr-xr-x`) or a chmod expression (`u+x,go-w`) applied to the current one.
+#[derive(Debug, PartialEq, Clone)]
+pub enum ModeSpec {
+    Absolute(FilePermission),
+    Expr(ChmodExpr),
+}
+
+impl ModeSpec {
+    /// Returns the permission that results from applying `self` to `current`.
+    /// The file type of `current` is always kept.
+    pub fn resolve(&self, current: &FilePermission) -> FilePermission {
+        match self {
+            ModeSpec::Absolute(permission) => current.with_bits(permission.to_bits()),
+            ModeSpec::Expr(expr) => current.apply_expr(expr),
+        }
+    }
+}
+
+impl FromStr for ModeSpec {
+    type Err = PermconError;
+
+    /// Tries to parse an absolute permission and then a chmod expression. If
+    /// both fail, the error of the chmod parser is returned unless `mode`
+    /// looks like an absolute permission (all digits, or 9 to 10 chars
+    /// without any of `+`, `=` and `,`).
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        let permission_error = match FilePermission::try_from(mode) {
+            Ok(permission) => return Ok(ModeSpec::Absolute(permission)),
+            Err(error) => error,
+        };
+
+        let expr_error = match ChmodExpr::from_str(mode) {
+            Ok(expr) => return Ok(ModeSpec::Expr(expr)),
+            Err(error) => error,
+        };
+
+        let length = mode.chars().count();
+        let is_numeric = !mode.is_empty() && mode.chars().all(|ch| ch.is_ascii_digit());
+        let is_symbolic = (9..=10).contains(&length) && !mode.contains(['+', '=', ',']);
+
+        if is_numeric || is_symbolic {
+            Err(permission_error)
+        } else {
+            Err(expr_error)
+        }
+    }
+}
+
+/// The permission of a path before and after setting a new mode.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PermissionChange {
+    pub path: PathBuf,
+    pub before: FilePermission,
+    pub after: FilePermission,
+}
+
+impl PermissionChange {
+    /// Returns `true` if the permission bits differ.
+    pub fn is_changed(&self) -> bool {
+        self.before.to_bits() != self.after.to_bits()
+    }
+}
+
+/// An I/O error of a tree walk and the path it happened at. A walk reports
+/// it and moves on to the next entry, like `chmod -R` and `find` do.
+#[derive(Debug)]
+pub struct WalkError {
+    pub path: PathBuf,
+    pub error: io::Error,
+}
+
+impl WalkError {
+    fn new(path: &Path, error: io::Error) -> Self {
+        WalkError {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+}
+
+impl fmt::Display for WalkError {
+    /// Formats the error like `find` does, e.g., `/srv/private: Permission
+    /// denied (os error 13)`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl error::Error for WalkError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Sets the permission of the file at `path` (following symbolic links, like
+/// `chmod`) and returns the change. If `dry_run` is `true` the file is left
+/// untouched and only the would-be change is reported.
+///
+/// ```rust,no_run
+/// use std::str::FromStr;
+/// use permcon::fs::{set_permission, ModeSpec};
+///
+/// let mode = ModeSpec::from_str("u+x,go-w").unwrap();
+/// let change = set_permission("deploy.sh", &mode, true).unwrap();
+///
+/// println!("{} -> {}", change.before.to_octal_str(), change.after.to_octal_str());
+/// ```
+pub fn set_permission<P: AsRef<Path>>(
+    path: P,
+    mode: &ModeSpec,
+    dry_run: bool,
+) -> io::Result<PermissionChange> {
+    let path = path.as_ref();
+    let before = FilePermission::from_path_follow_symlinks(path)?;
+
+    change_permission(path, before, mode, dry_run)
+}
+
+/// Options of [`set_permissions_recursive`]. A `None` mode leaves the
+/// matching files untouched.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct WalkOptions {
+    /// The mode applied to regular files.
+    pub file_mode: Option<ModeSpec>,
+    /// The mode applied to directories, including the root.
+    pub directory_mode: Option<ModeSpec>,
+    /// Follow symbolic links (and descend into linked directories) instead of
+    /// skipping them.
+    pub follow_symlinks: bool,
+    /// Only report what would change, don't touch the files.
+    pub dry_run: bool,
+}
+
+/// Walks the tree under `root` and sets the permission of every directory
+/// and regular file with the matching mode of `options`. Other file types
+/// (devices, sockets, pipes) are skipped. Directories are changed before
+/// their entries are read and entries are visited in sorted order.
+///
+/// Returns the change of every visited path, in visiting order. An error
+/// (e.g., an unreadable directory) doesn't stop the walk: it takes the place
+/// of the change and the walk goes on with the next entry, like `chmod -R`.
+///
+/// ```rust,no_run
+/// use std::str::FromStr;
+/// use permcon::fs::{set_permissions_recursive, ModeSpec, WalkOptions};
+///
+/// // dirs 0755, files 0644, but files that are already executable keep it
+/// let options = WalkOptions {
+///     directory_mode: Some(ModeSpec::from_str("0755").unwrap()),
+///     file_mode: Some(ModeSpec::from_str("u=rwX,go=rX").unwrap()),
+///     ..WalkOptions::default()
+/// };
+///
+/// for result in set_permissions_recursive("site", &options) {
+///     match result {
+///         Ok(change) => println!("{}: {}", change.path.display(), change.after.to_octal_str()),
+///         Err(error) => eprintln!("{error}"),
+///     }
+/// }
+/// ```
+pub fn set_permissions_recursive<P: AsRef<Path>>(
+    root: P,
+    options: &WalkOptions,
+) -> Vec<Result<PermissionChange, WalkError>> {
+    let mut results = Vec::new();
+    let mut visited_directories = HashSet::new();
+
+    walk(
+        root.as_ref(),
+        options,
+        &mut visited_directories,
+        &mut results,
+    );
+
+    results
+}
+
+fn walk(
+    path: &Path,
+    options: &WalkOptions,
+    visited_directories: &mut HashSet<(u64, u64)>,
+    results: &mut Vec<Result<PermissionChange, WalkError>>,
+) {
+    let metadata = match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => {
+            if !options.follow_symlinks {
+                return;
+            }
+
+            fs::metadata(path)
+        }
+        metadata => metadata,
+    };
+
+    let metadata = match metadata {
+        Ok(metadata) => metadata,
+        Err(error) => return results.push(Err(WalkError::new(path, error))),
+    };
+
+    let permission = FilePermission::from_metadata(&metadata);
+    let is_directory = permission.filetype == FileType::Directory;
+
+    let mode = match permission.filetype {
+        FileType::Directory => &options.directory_mode,
+        FileType::Regular => &options.file_mode,
+        _ => return,
+    };
+
+    // the (device, inode) pair guards against symlink loops, a directory is
+    // changed and read only once
+    if is_directory && !visited_directories.insert((metadata.dev(), metadata.ino())) {
+        return;
+    }
+
+    if let Some(mode) = mode {
+        results.push(
+            change_permission(path, permission, mode, options.dry_run)
+                .map_err(|error| WalkError::new(path, error)),
+        );
+    }
+
+    if !is_directory {
+        return;
+    }
+
+    let entries = match read_sorted_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return results.push(Err(error)),
+    };
+
+    for entry in entries {
+        walk(&entry, options, visited_directories, results);
+    }
+}
+
+/// Walks the tree under `root` like `find root -perm spec` and returns the
+/// path and permission of every file (including `root`) that `matcher`
+/// matches, in sorted order. Symbolic links are neither followed nor
+/// descended into and are tested with their own permission.
+///
+/// Calls `on_result` with every match as soon as it's found. An error
+/// (e.g., an unreadable directory) is passed to `on_result` as well and the
+/// walk goes on with the next entry, like `find` does.
+///
+/// ```rust,no_run
+/// use std::str::FromStr;
+/// use permcon::find::PermMatcher;
+/// use permcon::fs::find_permissions;
+///
+/// let matcher = PermMatcher::from_str("-o+w").unwrap();
+///
+/// find_permissions("/srv", &matcher, |result| match result {
+///     Ok((path, permission)) => println!("{} {}", permission.to_octal_str(), path.display()),
+///     Err(error) => eprintln!("{error}"),
+/// });
+/// ```
+pub fn find_permissions<P, F>(root: P, matcher: &PermMatcher, mut on_result: F)
+where
+    P: AsRef<Path>,
+    F: FnMut(Result<(PathBuf, FilePermission), WalkError>),
+{
+    find_walk(root.as_ref(), matcher, &mut on_result);
+}
+
+fn find_walk<F>(path: &Path, matcher: &PermMatcher, on_result: &mut F)
+where
+    F: FnMut(Result<(PathBuf, FilePermission), WalkError>),
+{
+    let permission = match FilePermission::from_path(path) {
+        Ok(permission) => permission,
+        Err(error) => return on_result(Err(WalkError::new(path, error))),
+    };
+    let is_directory = permission.filetype == FileType::Directory;
+
+    if matcher.matches(&permission) {
+        on_result(Ok((path.to_path_buf(), permission)));
+    }
+
+    if !is_directory {
+        return;
+    }
+
+    let entries = match read_sorted_dir(path) {
+        Ok(entries) => entries,
+        Err(error) => return on_result(Err(error)),
+    };
+
+    for entry in entries {
+        find_walk(&entry, matcher, on_result);
+    }
+}
+
+/// Returns the paths of the entries of the directory at `path` in sorted
+/// order.
+fn read_sorted_dir(path: &Path) -> Result<Vec<PathBuf>, WalkError> {
+    let mut entries = fs::read_dir(path)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .map_err(|error| WalkError::new(path, error))?;
+    entries.sort();
+
+    Ok(entries)
+}
+
+fn change_permission(
+    path: &Path,
+    before: FilePermission,
+    mode: &ModeSpec,
+    dry_run: bool,
+) -> io::Result<PermissionChange> {
+    let after = mode.resolve(&before);
+
+    if !dry_run {
+        after.write_to_path(path)?;
+    }
+
+    Ok(PermissionChange {
+        path: path.to_path_buf(),
+        before,
+        after,
+    })
+}
+
+impl FilePermission {
+    /// Reads the permission of the file at `path` from the filesystem. Like
+    /// `ls -l`, symbolic links are not followed, so a link reports its own
+    /// permission and file type.
+    ///
+    /// ```rust
+    /// use permcon::{FilePermission, FileType};
+    ///
+    /// let perm = FilePermission::from_path("/").unwrap();
+    /// assert_eq!(perm.filetype, FileType::Directory);
+    /// ```
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::from_metadata(&fs::symlink_metadata(path)?))
+    }
+
+    /// Same as [`FilePermission::from_path`] but follows symbolic links and
+    /// reads the permission of the target instead.
+    pub fn from_path_follow_symlinks<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::from_metadata(&fs::metadata(path)?))
+    }
+
+    /// Sets the permission and special bits of the file at `path` to `self`.
+    /// The file type is ignored.
+    pub fn write_to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::set_permissions(path, fs::Permissions::from_mode(self.to_bits() as u32))
+    }
+
+    /// Builds a `FilePermission` from the `st_mode` of already fetched
+    /// metadata.
+    pub fn from_metadata(metadata: &fs::Metadata) -> Self {
+        let mode = metadata.mode();
+        Self::from_bits((mode & 0o7777) as u16, FileType::from_mode(mode))
+    }
+}
+
+impl Mode {
+    /// Returns the raw `st_mode` of already fetched metadata.
+    pub fn from_metadata(metadata: &fs::Metadata) -> Self {
+        Mode::new(metadata.mode())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        os::unix::fs::symlink,
+        sync::atomic::{self, AtomicUsize},
+    };
+
+    /// Creates an empty directory that no other test or run shares.
+    fn create_temp_dir(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "permcon-{name}-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, atomic::Ordering::Relaxed)
+        ));
+
+        // a leftover of an earlier run with the same process id
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_from_path() {
+        let dir = create_temp_dir("from-path");
+
+        let file = dir.join("file");
+        let link = dir.join("link");
+        fs::write(&file, "").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o4751)).unwrap();
+        symlink(&file, &link).unwrap();
+
+        let perm = FilePermission::from_path(&file).unwrap();
+        assert_eq!(perm.to_symbolic_str(), "-rwsr-x--x");
+        assert_eq!(perm.filetype, FileType::Regular);
+
+        let perm = FilePermission::from_path(&link).unwrap();
+        assert_eq!(perm.filetype, FileType::Symlink);
+
+        let perm = FilePermission::from_path_follow_symlinks(&link).unwrap();
+        assert_eq!(perm.to_octal_str(), "4751");
+
+        let perm = FilePermission::from_path(&dir).unwrap();
+        assert_eq!(perm.filetype, FileType::Directory);
+
+        assert!(FilePermission::from_path(dir.join("missing")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_set_permission() {
+        let dir = create_temp_dir("set-permission");
+        let file = dir.join("script.sh");
+        fs::write(&file, "").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let mode = ModeSpec::from_str("u+x,g+s").unwrap();
+        let change = set_permission(&file, &mode, true).unwrap();
+
+        assert!(change.is_changed());
+        assert_eq!(change.after.to_symbolic_str(), "-rwxr-Sr--");
+        assert_eq!(
+            FilePermission::from_path(&file).unwrap().to_octal_str(),
+            "0644"
+        );
+
+        let change = set_permission(&file, &mode, false).unwrap();
+        assert_eq!(FilePermission::from_path(&file).unwrap(), change.after);
+
+        let mode = ModeSpec::from_str("0640").unwrap();
+        set_permission(&file, &mode, false).unwrap();
+        let change = set_permission(&file, &mode, false).unwrap();
+
+        assert!(!change.is_changed());
+        assert_eq!(change.after.to_symbolic_str(), "-rw-r-----");
+
+        assert!(ModeSpec::from_str("u+z").is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_set_permissions_recursive() {
+        let dir = create_temp_dir("recursive");
+        let outside = create_temp_dir("recursive-outside");
+
+        let bin = dir.join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::set_permissions(&bin, fs::Permissions::from_mode(0o700)).unwrap();
+
+        let script = bin.join("run.sh");
+        let readme = dir.join("README");
+        let target = outside.join("target");
+
+        for (file, mode) in [(&script, 0o700), (&readme, 0o600), (&target, 0o600)] {
+            fs::write(file, "").unwrap();
+            fs::set_permissions(file, fs::Permissions::from_mode(mode)).unwrap();
+        }
+
+        symlink(&target, dir.join("link")).unwrap();
+        symlink(&dir, bin.join("loop")).unwrap();
+
+        let mut options = WalkOptions {
+            directory_mode: Some(ModeSpec::from_str("0755").unwrap()),
+            file_mode: Some(ModeSpec::from_str("u=rwX,go=rX").unwrap()),
+            ..WalkOptions::default()
+        };
+
+        let changes = set_permissions_recursive(&dir, &options)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let summary: Vec<_> = changes
+            .iter()
+            .map(|change| {
+                (
+                    change.path.strip_prefix(&dir).unwrap().to_path_buf(),
+                    change.after.to_symbolic_str(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                (PathBuf::new(), String::from("drwxr-xr-x")),
+                (PathBuf::from("README"), String::from("-rw-r--r--")),
+                (PathBuf::from("bin"), String::from("drwxr-xr-x")),
+                (PathBuf::from("bin/run.sh"), String::from("-rwxr-xr-x")),
+            ]
+        );
+
+        for change in &changes {
+            assert_eq!(
+                FilePermission::from_path(&change.path).unwrap(),
+                change.after
+            );
+        }
+
+        assert_eq!(
+            FilePermission::from_path(&target).unwrap().to_octal_str(),
+            "0600"
+        );
+
+        options.follow_symlinks = true;
+        options.dry_run = true;
+
+        let changes = set_permissions_recursive(&dir, &options)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        let link_change = changes
+            .iter()
+            .find(|change| change.path == dir.join("link"))
+            .unwrap();
+
+        // the `bin/loop` link to the root isn't changed again
+        assert_eq!(changes.len(), 5);
+        assert_eq!(link_change.after.to_octal_str(), "0644");
+        assert_eq!(
+            FilePermission::from_path(&target).unwrap().to_octal_str(),
+            "0600"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+        fs::remove_dir_all(&outside).unwrap();
+    }
+
+    #[test]
+    fn test_set_permissions_recursive_continues_after_errors() {
+        let dir = create_temp_dir("recursive-errors");
+        let dangling = dir.join("a-dangling");
+        let file = dir.join("b-notes");
+
+        symlink(dir.join("missing"), &dangling).unwrap();
+        fs::write(&file, "").unwrap();
+        fs::set_permissions(&file, fs::Permissions::from_mode(0o600)).unwrap();
+
+        let options = WalkOptions {
+            file_mode: Some(ModeSpec::from_str("0644").unwrap()),
+            follow_symlinks: true,
+            ..WalkOptions::default()
+        };
+
+        // the link can't be followed, the file after it is changed anyway
+        let results = set_permissions_recursive(&dir, &options);
+
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap_err().path, dangling);
+        assert_eq!(
+            results[0].as_ref().unwrap_err().error.kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(results[1].as_ref().unwrap().path, file);
+        assert_eq!(
+            FilePermission::from_path(&file).unwrap().to_octal_str(),
+            "0644"
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_permissions() {
+        let dir = create_temp_dir("find");
+        let shared = dir.join("shared");
+        fs::create_dir_all(&shared).unwrap();
+
+        let files = [
+            (dir.join("notes"), 0o644),
+            (shared.join("drop"), 0o666),
+            (shared.join("tool"), 0o4755),
+        ];
+
+        for (file, mode) in &files {
+            fs::write(file, "").unwrap();
+            fs::set_permissions(file, fs::Permissions::from_mode(*mode)).unwrap();
+        }
+
+        fs::set_permissions(&shared, fs::Permissions::from_mode(0o1777)).unwrap();
+        symlink(dir.join("notes"), shared.join("link")).unwrap();
+
+        let find = |spec: &str| -> Vec<PathBuf> {
+            let mut found = Vec::new();
+
+            find_permissions(&dir, &PermMatcher::from_str(spec).unwrap(), |result| {
+                let (path, _) = result.unwrap();
+                found.push(path.strip_prefix(&dir).unwrap().to_path_buf());
+            });
+
+            found
+        };
+
+        // the link matches with its own `0777`
+        assert_eq!(
+            find("-o+w"),
+            ["shared", "shared/drop", "shared/link"].map(PathBuf::from)
+        );
+        assert_eq!(find("/6000"), [PathBuf::from("shared/tool")]);
+        assert_eq!(find("644"), [PathBuf::from("notes")]);
+        assert_eq!(find("/000").len(), 6);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_find_permissions_continues_after_errors() {
+        let dir = create_temp_dir("find-errors");
+        let gone = dir.join("a-gone");
+        let tool = dir.join("b-ok").join("tool");
+
+        fs::create_dir_all(&gone).unwrap();
+        fs::create_dir_all(tool.parent().unwrap()).unwrap();
+        fs::write(&tool, "").unwrap();
+        fs::set_permissions(&gone, fs::Permissions::from_mode(0o1755)).unwrap();
+        fs::set_permissions(&tool, fs::Permissions::from_mode(0o4755)).unwrap();
+
+        let mut found = Vec::new();
+        let mut errors = Vec::new();
+
+        // a directory is reported before it's read, removing it then makes
+        // reading it fail (even as root) and the walk goes on
+        find_permissions(
+            &dir,
+            &PermMatcher::from_str("/7000").unwrap(),
+            |result| match result {
+                Ok((path, _)) => {
+                    if path == gone {
+                        fs::remove_dir(&path).unwrap();
+                    }
+
+                    found.push(path);
+                }
+                Err(error) => errors.push(error.path),
+            },
+        );
+
+        assert_eq!(found, [gone.as_path(), tool.as_path()]);
+        assert_eq!(errors, [gone.as_path()]);
+
+        let mut errors = Vec::new();
+
+        find_permissions(
+            dir.join("missing"),
+            &PermMatcher::from_str("/000").unwrap(),
+            |result| errors.push(result.unwrap_err().error.kind()),
+        );
+
+        assert_eq!(errors, [io::ErrorKind::NotFound]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,372 @@
+use crate::{error::PermconError, filetype::FileType, perm::FilePermission};
+use lazy_static::lazy_static;
+use regex::Regex;
+use std::{fmt, str::FromStr};
+
+lazy_static! {
+    /// A pattern to parse a line of `git ls-tree [-l]`
+    /// (`<mode> <type> <object> [<size>]\t<path>`) or `git ls-files -s`
+    /// (`<mode> <object> <stage>\t<path>`).
+    pub static ref GIT_ENTRY_PATTERN: Regex = Regex::new(
+        r"(?x)^
+        (?P<mode>[0-7]{6})
+        \x20 (?:
+            (?P<type>blob|tree|commit) \x20 (?P<tree_object>[0-9a-f]{40}|[0-9a-f]{64}) (?:\x20+\S+)?
+            | (?P<index_object>[0-9a-f]{40}|[0-9a-f]{64}) \x20 (?P<stage>[0-3])
+        )
+        \t (?P<path>.+)
+        $"
+    )
+    .unwrap();
+}
+
+/// The modes git records for a tree entry. Git doesn't store permissions,
+/// only whether a file is executable, so every other bit is dropped.
+///
+/// ```rust
+/// use permcon::{git::GitMode, FilePermission};
+///
+/// let perm = FilePermission::try_from("-rwxrwxr-x").unwrap();
+///
+/// assert_eq!(perm.to_git_mode(), Some(GitMode::Executable));
+/// assert_eq!(GitMode::Executable.to_string(), "100755");
+///
+/// // the group write bit doesn't survive a commit
+/// assert_eq!(perm.git_dropped_bits().unwrap().to_symbolic_str(), "-----w----");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum GitMode {
+    /// `100644`
+    Regular,
+    /// `100755`
+    Executable,
+    /// `120000`
+    Symlink,
+    /// `040000`: a directory.
+    Tree,
+    /// `160000`: a submodule.
+    Gitlink,
+}
+
+impl GitMode {
+    /// All the git modes.
+    pub const ALL: [GitMode; 5] = [
+        GitMode::Regular,
+        GitMode::Executable,
+        GitMode::Symlink,
+        GitMode::Tree,
+        GitMode::Gitlink,
+    ];
+
+    /// Returns the mode as git writes it, e.g., `0o100644`.
+    pub const fn bits(self) -> u32 {
+        match self {
+            GitMode::Regular => 0o100644,
+            GitMode::Executable => 0o100755,
+            GitMode::Symlink => 0o120000,
+            GitMode::Tree => 0o040000,
+            GitMode::Gitlink => 0o160000,
+        }
+    }
+
+    /// Returns the git mode of `bits`, `None` if git never records it.
+    pub const fn from_bits(bits: u32) -> Option<Self> {
+        match bits {
+            0o100644 => Some(GitMode::Regular),
+            0o100755 => Some(GitMode::Executable),
+            0o120000 => Some(GitMode::Symlink),
+            0o040000 => Some(GitMode::Tree),
+            0o160000 => Some(GitMode::Gitlink),
+            _ => None,
+        }
+    }
+
+    /// Returns a short description (e.g., `executable file`).
+    pub const fn description(self) -> &'static str {
+        match self {
+            GitMode::Regular => "regular file",
+            GitMode::Executable => "executable file",
+            GitMode::Symlink => "symbolic link",
+            GitMode::Tree => "directory",
+            GitMode::Gitlink => "submodule",
+        }
+    }
+}
+
+impl fmt::Display for GitMode {
+    /// Formats the mode as git writes it, e.g., `040000`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:06o}", self.bits())
+    }
+}
+
+impl FromStr for GitMode {
+    type Err = PermconError;
+
+    /// Tries to parse a mode as git writes it (e.g., `100755`).
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        GitMode::ALL
+            .into_iter()
+            .find(|git_mode| git_mode.to_string() == mode)
+            .ok_or_else(|| PermconError::InvalidValue {
+                input: mode.to_string(),
+                expected: String::from("one of 100644, 100755, 120000, 040000, 160000"),
+            })
+    }
+}
+
+impl FilePermission {
+    /// Returns the mode git would record for a file with this permission,
+    /// `None` for the file types git can't track (e.g., devices and named
+    /// pipes). A permission without a file type is taken as a regular file.
+    /// A directory maps to a tree, as a submodule can't be told apart by its
+    /// permission.
+    pub fn to_git_mode(&self) -> Option<GitMode> {
+        match self.filetype {
+            FileType::Regular | FileType::Unknown if self.user.execute => Some(GitMode::Executable),
+            FileType::Regular | FileType::Unknown => Some(GitMode::Regular),
+            FileType::Symlink => Some(GitMode::Symlink),
+            FileType::Directory => Some(GitMode::Tree),
+            _ => None,
+        }
+    }
+
+    /// Returns the bits of a regular file git silently drops when recording
+    /// it, i.e., the ones not in `0644` or `0755`. Returns `None` for the
+    /// other file types, as git keeps no permission of them at all.
+    pub fn git_dropped_bits(&self) -> Option<FilePermission> {
+        let recorded = self.to_git_mode()?.bits();
+
+        if !matches!(self.filetype, FileType::Regular | FileType::Unknown) {
+            return None;
+        }
+
+        Some(self.with_bits(self.to_bits() & !(recorded as u16 & 0o7777)))
+    }
+}
+
+/// Represents a parsed line of `git ls-tree` or `git ls-files -s`.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::{git::{GitEntry, GitMode}, FilePermission};
+///
+/// let entry = GitEntry::from_str(
+///     "100644 blob e69de29bb2d1d6434b8b29ae775ad8c2e48c5391\tbuild.sh",
+/// ).unwrap();
+///
+/// assert_eq!(entry.mode, GitMode::Regular);
+/// assert_eq!(entry.object_type.as_deref(), Some("blob"));
+/// assert_eq!(entry.path, "build.sh");
+///
+/// // the file got `chmod +x` in the working tree
+/// let worktree = FilePermission::try_from("-rwxr-xr-x").unwrap();
+/// assert!(!entry.matches_worktree(&worktree));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GitEntry {
+    pub mode: GitMode,
+    /// The object type (`blob`, `tree` or `commit`) of a `git ls-tree` line.
+    pub object_type: Option<String>,
+    /// The object name (hash).
+    pub object: String,
+    /// The merge stage of a `git ls-files -s` line, `0` unless in a conflict.
+    pub stage: Option<u8>,
+    /// The path, unquoted if git printed it C-quoted (e.g.,
+    /// `"na\303\257ve.sh"` for `naïve.sh`).
+    pub path: String,
+}
+
+impl GitEntry {
+    /// Checks whether git would record `worktree`, the permission of the file
+    /// in the working tree, with the mode of this entry. A submodule matches
+    /// any directory.
+    pub fn matches_worktree(&self, worktree: &FilePermission) -> bool {
+        match (self.mode, worktree.to_git_mode()) {
+            (GitMode::Gitlink, Some(GitMode::Tree)) => true,
+            (mode, worktree_mode) => Some(mode) == worktree_mode,
+        }
+    }
+}
+
+impl FromStr for GitEntry {
+    type Err = PermconError;
+
+    /// Tries to parse a line printed by `git ls-tree`, `git ls-tree -l` or
+    /// `git ls-files -s`.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let caps =
+            GIT_ENTRY_PATTERN
+                .captures(line)
+                .ok_or_else(|| PermconError::InvalidGitLine {
+                    input: line.to_string(),
+                })?;
+
+        let column = |name: &str| caps.name(name).map(|value| value.as_str().to_string());
+        let path = column("path").unwrap();
+        let path = match path
+            .strip_prefix('"')
+            .and_then(|path| path.strip_suffix('"'))
+        {
+            Some(quoted) => unquote_c_path(quoted).ok_or_else(|| PermconError::InvalidGitLine {
+                input: line.to_string(),
+            })?,
+            None => path,
+        };
+
+        Ok(GitEntry {
+            mode: GitMode::from_str(&column("mode").unwrap())?,
+            object_type: column("type"),
+            object: column("tree_object")
+                .or_else(|| column("index_object"))
+                .unwrap(),
+            stage: column("stage").map(|stage| stage.parse().unwrap()),
+            path,
+        })
+    }
+}
+
+/// Reverses the C-style quoting git applies to paths with control chars,
+/// `"`, `\` or (unless `core.quotePath` is off) non-ASCII bytes, which are
+/// written as octal escapes of their UTF-8 bytes. Returns `None` for a
+/// malformed escape or a path that isn't valid UTF-8.
+fn unquote_c_path(quoted: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut chars = quoted.chars();
+
+    while let Some(ch) = chars.next() {
+        if ch != '\\' {
+            let mut buffer = [0; 4];
+            bytes.extend_from_slice(ch.encode_utf8(&mut buffer).as_bytes());
+            continue;
+        }
+
+        let byte = match chars.next()? {
+            'a' => 0x07,
+            'b' => 0x08,
+            't' => b'\t',
+            'n' => b'\n',
+            'v' => 0x0b,
+            'f' => 0x0c,
+            'r' => b'\r',
+            '"' => b'"',
+            '\\' => b'\\',
+            first @ '0'..='3' => {
+                let digits = [first, chars.next()?, chars.next()?];
+                let octal: String = digits.iter().collect();
+                u8::from_str_radix(&octal, 8).ok()?
+            }
+            _ => return None,
+        };
+
+        bytes.push(byte);
+    }
+
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OBJECT: &str = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391";
+
+    #[test]
+    fn test_git_modes() {
+        // (permission, git mode, dropped bits)
+        let test_cases: [(&str, Option<GitMode>, Option<&str>); 9] = [
+            ("-rw-r--r--", Some(GitMode::Regular), Some("0000")),
+            ("0600", Some(GitMode::Regular), Some("0000")),
+            ("-rw-rw-rw-", Some(GitMode::Regular), Some("0022")),
+            ("4755", Some(GitMode::Executable), Some("4000")),
+            ("-rwxrwx---", Some(GitMode::Executable), Some("0020")),
+            ("-rw-r-xr-x", Some(GitMode::Regular), Some("0011")),
+            ("lrwxrwxrwx", Some(GitMode::Symlink), None),
+            ("drwx------", Some(GitMode::Tree), None),
+            ("crw-rw-rw-", None, None),
+        ];
+
+        for (perm, git_mode, dropped) in test_cases {
+            let perm = FilePermission::try_from(perm).unwrap();
+            let dropped_bits = perm.git_dropped_bits();
+
+            assert_eq!(perm.to_git_mode(), git_mode, "{perm:?}");
+            assert_eq!(
+                dropped_bits
+                    .as_ref()
+                    .map(FilePermission::to_octal_str)
+                    .as_deref(),
+                dropped,
+                "{perm:?}"
+            );
+        }
+
+        for git_mode in GitMode::ALL {
+            assert_eq!(GitMode::from_str(&git_mode.to_string()), Ok(git_mode));
+            assert_eq!(GitMode::from_bits(git_mode.bits()), Some(git_mode));
+        }
+
+        assert!(GitMode::from_str("100664").is_err());
+    }
+
+    #[test]
+    fn test_parse_git_lines() {
+        let ls_tree = GitEntry::from_str(&format!("040000 tree {OBJECT}\tsrc")).unwrap();
+        assert_eq!(ls_tree.mode, GitMode::Tree);
+        assert_eq!(ls_tree.object, OBJECT);
+        assert_eq!(ls_tree.stage, None);
+
+        let ls_tree_long =
+            GitEntry::from_str(&format!("100755 blob {OBJECT}    1234\tbin/run me")).unwrap();
+        assert_eq!(ls_tree_long.mode, GitMode::Executable);
+        assert_eq!(ls_tree_long.path, "bin/run me");
+
+        let ls_files = GitEntry::from_str(&format!("160000 {OBJECT} 0\tvendor/lib")).unwrap();
+        assert_eq!(ls_files.mode, GitMode::Gitlink);
+        assert_eq!(ls_files.object_type, None);
+        assert_eq!(ls_files.stage, Some(0));
+        assert!(ls_files.matches_worktree(&FilePermission::try_from("drwxr-xr-x").unwrap()));
+
+        for line in [
+            String::from("100644 README.md"),
+            format!("100644 blob {OBJECT} README.md"),
+            format!("100644 {OBJECT} 4\tREADME.md"),
+        ] {
+            assert_eq!(
+                GitEntry::from_str(&line),
+                Err(PermconError::InvalidGitLine {
+                    input: line.clone()
+                })
+            );
+        }
+
+        assert!(GitEntry::from_str(&format!("100664 blob {OBJECT}\tREADME.md")).is_err());
+    }
+
+    #[test]
+    fn test_parse_quoted_paths() {
+        let test_cases: [(&str, Option<&str>); 5] = [
+            (r#""dir/na\303\257ve.sh""#, Some("dir/naïve.sh")),
+            (
+                r#""tab\there \"quoted\" back\\slash""#,
+                Some("tab\there \"quoted\" back\\slash"),
+            ),
+            ("\"plain\"", Some("plain")),
+            (r#""bad\q""#, None),
+            (r#""invalid\377utf-8""#, None),
+        ];
+
+        for (quoted, path) in test_cases {
+            let line = format!("100755 blob {OBJECT}\t{quoted}");
+
+            assert_eq!(
+                GitEntry::from_str(&line)
+                    .map(|entry| entry.path)
+                    .ok()
+                    .as_deref(),
+                path,
+                "{quoted}"
+            );
+        }
+    }
+}
@@ -4,8 +4,7 @@
 //! ## Library Usages
 //!  
 //! ```rust
-//! use permcon::{FilePermission, GroupPermission, SourceFormat, SpecialPermission};
-//! use permcon::utils::get_filetype_from_char;
+//! use permcon::{FileType, FilePermission, GroupPermission, SourceFormat, SpecialPermission};
 //!
 //! let perm = FilePermission::try_from("-rwxr-xr-T").unwrap();
 //!
@@ -28,8 +27,7 @@
 //!         execute: false,
 //!         special: true,
 //!     },
-//!     filetype_char: '-',
-//!     filetype: get_filetype_from_char('-'),
+//!     filetype: FileType::Regular,
 //!     source_format: Some(SourceFormat::Symbolic),
 //!     special: [SpecialPermission::Nil, SpecialPermission::Nil, SpecialPermission::StickyBit],
 //! });
@@ -49,6 +47,13 @@
 //! ❯ permcon -- -rwxrwxr-t
 //! # 1775
 //!
+//! # the `+`, `.` or `@` that `ls -l` prints after the mode is accepted. With an
+//! # ACL (`+`) the group bits are the ACL mask; the ACL entries themselves can be
+//! # parsed (e.g., from `getfacl`) with the `permcon::acl` module of the library
+//! ❯ permcon -- -rwxrwxr-x+
+//! # 0775
+//! # note: "-rwxrwxr-x+" has an ACL, so its group bits are the ACL mask
+//!
 //! ❯ permcon -a -- -rwxrwxr-t
 //! # file type    : Regular File
 //! # symbolic     : -rwxrwxr-t
@@ -70,12 +75,159 @@
 //! # other(r-T, 4): read, _    , (_, StickyBit)
 //! # ------------------------
 //! # special permissions: StickyBit
+//!
+//! ❯ permcon 0644 --apply 'u+x,g+s'
+//! # symbolic     : -rwxr-Sr--
+//! # octal        : 2744
+//!
+//! ❯ permcon 4750 --to chmod
+//! # u=rwx,g=rx,o=,u+s
+//!
+//! # `--to` also takes `octal`, `short-octal`, `symbolic`, `symbolic-bits`, `mode`, `json` and `yaml`
+//! ❯ permcon 4755 --to table
+//! # class  read  write  execute  special
+//! # user   yes   yes    yes      setuid
+//! # group  yes   no     yes      no
+//! # other  yes   no     yes      no
+//!
+//! ❯ permcon --to description -- drwxrwxrwt
+//! # The owner can read, write and execute; the group can read, write and execute; others can read, write and execute. Only the owner of an entry can delete or rename it (sticky).
+//!
+//! ❯ permcon --format '{octal} {symbolic} {filetype}' -- drwxr-xr-x
+//! # 0755 drwxr-xr-x Directory
+//!
+//! # convert many at once, in order. Failures are reported and skipped, and the exit code is 1
+//! ❯ permcon 0755 rwxr-x--- 9999
+//! # -rwxr-xr-x
+//! # 0750
+//! # Invalid octal digit 9 at position 0 of "9999": it must be within 0..=7!
+//! # 1 of 3 permission(s) failed
+//!
+//! # one permission per line, JSON Lines with `--json`
+//! ❯ cut -d' ' -f1 modes.txt | permcon --stdin --json | jq -r .octal
+//!
+//! # full `st_mode`s in octal or hex (`stat -c %f`) carry the file type
+//! ❯ permcon 0120777
+//! # lrwxrwxrwx
+//!
+//! ❯ permcon --to mode -- drwxr-xr-x
+//! # 040755
+//!
+//! ❯ permcon --umask 027
+//! # umask        : 0027 (u=rwx,g=rx,o=)
+//! # file         : -rw-r----- (0640)
+//! # directory    : drwxr-x--- (0750)
+//!
+//! ❯ permcon --path /tmp --path /dev/null
+//! # drwxrwxrwt 1777 /tmp
+//! # crw-rw-rw- 0666 /dev/null
+//!
+//! ❯ permcon set u+x,go-w deploy.sh README.md --dry-run
+//! # deploy.sh: -rw-rw-r-- (0664) -> -rwxr--r-- (0744)
+//! # README.md: -rw-r--r-- (0644) -> -rwxr--r-- (0744)
+//! # 2 of 2 file(s) would change
+//!
+//! ❯ ls -la /tmp | permcon --ls
+//! #      total 8
+//! # 1777 drwxrwxrwt 12 root root 4096 Oct 17 10:00 .
+//! # 0755 drwxr-xr-x 20 root root 4096 Oct 17 06:38 ..
+//! # 0644 -rw-r--r--  1 sifat users   12 Oct 17 10:00 notes.txt
+//!
+//! # a member of the owning group is denied although others are allowed
+//! ❯ permcon check 0705 --owner 1000:1000 --as 1001:1000,27 --access rx
+//! # class        : Group
+//! # granted      : ---
+//! # requested    : r-x (denied)
+//!
+//! ❯ permcon lint --fail-on warning 0777 ~/.ssh/id_rsa -- -rwSr--r--
+//! # 0777: error [world-writable-without-sticky] anyone can modify it, or delete and rename its entries, as the sticky bit is not set
+//! # -rwSr--r--: warning [setuid-without-execute] the setuid bit is set but the owner can't execute it (`S`), which is most likely a mistake
+//!
+//! # changed positions are highlighted
+//! ❯ permcon diff 0755 -- -rwsr-x---
+//! # from         : -rwxr-xr-x (0755)
+//! # to           : -rwsr-x--- (4750)
+//! # chmod        : u+s,o-rx
+//!
+//! # is it at most as permissive as 0755? Exits with 1 if not
+//! ❯ permcon cmp 4752 0755
+//! # a            : -rwsr-x-w- (4752)
+//! # b            : -rwxr-xr-x (0755)
+//! # a ⊆ b        : no
+//! # only in a    : ---S----w- (4002)
+//!
+//! # like `find /usr/bin -perm /6000`
+//! ❯ permcon find /usr/bin --perm /6000
+//! # -rwxr-sr-x 2755 /usr/bin/chage
+//! # -rwsr-xr-x 4755 /usr/bin/passwd
+//!
+//! # compare the working tree to what git recorded, without running git itself
+//! ❯ git ls-tree HEAD | permcon git
+//! # 100755 executable file deploy.sh
+//! #     ! working tree: 100644 (-rw-rw-r--)
+//! #     ! dropped by git: g-w (-rw-rw-r--)
+//! # 040000 directory       src
+//!
+//! # audit a release tarball, then write a copy with dirs 0755 and files 0644 (or 0755 if executable)
+//! ❯ permcon tar release.tar --fail-on info
+//! # drwxr-xr-x 0755 root/root release/
+//! # -rwsr-xr-x 4755 root/root release/bin/run
+//! #     info [setid-file] it runs with the privileges of its owner or group, make sure it has to
+//! # -rw-rw-rw- 0666 root/root release/share/notes.txt
+//! #     error [world-writable-without-sticky] anyone can modify it, or delete and rename its entries, as the sticky bit is not set
+//!
+//! ❯ permcon tar release.tar --normalize release.normalized.tar
+//! # release/: drwxr-xr-x (0755) -> drwxr-xr-x (0755) (unchanged)
+//! # release/bin/run: -rwsr-xr-x (4755) -> -rwxr-xr-x (0755)
+//! # release/share/notes.txt: -rw-rw-rw- (0666) -> -rw-r--r-- (0644)
+//! # 2 of 3 entries changed, written to release.normalized.tar
+//!
+//! # scripts that lose their execute bit, entries without a Unix mode
+//! ❯ permcon zip release.zip
+//! # drwxr-xr-x        0 2025-10-17 10:00 release/
+//! # -rw-r--r--       23 2025-10-17 10:00 release/install.sh
+//! #     warning [script-without-execute] the script is extracted without execute permission
+//! # -?????????        7 2025-10-17 10:00 release/README.txt
+//! #     warning [missing-unix-mode] the entry has no Unix mode, so it is extracted with the default permission and loses any execute bit
+//!
+//! # the JSON Schema of the `--json` output
+//! ❯ permcon --json-schema > file-permission.schema.json
+//!
+//! ❯ permcon rwxr-xr-z
+//! # Invalid char 'z' at position 8 of "rwxr-xr-z": expected one of 'x', 't', 'T', '-'!
+//! #
+//! #     rwxr-xr-z
+//! #             ^
 //! ```
 
+pub mod access;
+pub mod acl;
+pub mod algebra;
+pub mod chmod;
+pub mod diff;
+pub mod error;
+pub mod filetype;
+pub mod find;
+#[cfg(unix)]
+pub mod fs;
+pub mod git;
+pub mod lint;
+pub mod ls;
+pub mod mode;
 pub mod octal;
 pub mod perm;
+pub mod render;
 pub mod symbolic;
+#[cfg(unix)]
+pub mod tar;
+pub mod umask;
 pub mod utils;
+pub mod zip;
+pub use chmod::ChmodExpr;
+pub use error::PermconError;
+pub use filetype::FileType;
+pub use mode::Mode;
 pub use octal::Octal;
 pub use perm::{FilePermission, GroupPermission, SourceFormat, SpecialPermission};
 pub use symbolic::Symbolic;
+pub use umask::Umask;
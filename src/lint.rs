@@ -0,0 +1,309 @@
+use crate::{
+    error::PermconError,
+    filetype::FileType,
+    perm::{FilePermission, SpecialPermission},
+};
+use std::{fmt, path::Path, str::FromStr};
+
+/// File names (or extensions, with a leading `.`) of private keys.
+const PRIVATE_KEY_NAMES: [&str; 8] = [
+    "id_rsa",
+    "id_dsa",
+    "id_ecdsa",
+    "id_ed25519",
+    ".key",
+    ".p12",
+    ".pfx",
+    ".ppk",
+];
+
+/// How risky a finding is. Ordered from the least to the most severe.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Severity {
+    type Err = PermconError;
+
+    /// Tries to parse `info`, `warning` or `error`.
+    fn from_str(severity: &str) -> Result<Self, Self::Err> {
+        match severity {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "error" => Ok(Severity::Error),
+            _ => Err(PermconError::InvalidValue {
+                input: severity.to_string(),
+                expected: String::from("one of 'info', 'warning', 'error'"),
+            }),
+        }
+    }
+}
+
+/// What a rule looks at: the permission and, if known, the path of the file.
+#[derive(Debug, Clone, Copy)]
+pub struct LintTarget<'a> {
+    pub permission: &'a FilePermission,
+    pub path: Option<&'a Path>,
+}
+
+/// A named check of a risky permission.
+#[derive(Debug, Clone, Copy)]
+pub struct Rule {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub explanation: &'static str,
+    /// Returns `true` if the target violates the rule.
+    pub check: fn(&LintTarget) -> bool,
+}
+
+/// A violated rule.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Finding {
+    pub id: &'static str,
+    pub severity: Severity,
+    pub explanation: &'static str,
+}
+
+/// All the rules [`lint`] checks.
+pub const RULES: [Rule; 7] = [
+    Rule {
+        id: "world-writable-without-sticky",
+        severity: Severity::Error,
+        explanation: "anyone can modify it, or delete and rename its entries, as the sticky bit is not set",
+        check: |target| target.permission.other.write && !has_special(target, 2),
+    },
+    Rule {
+        id: "world-writable-file",
+        severity: Severity::Error,
+        explanation: "anyone can modify it, the sticky bit only protects the entries of a directory",
+        // a permission without a file type might be a directory's
+        check: |target| {
+            target.permission.other.write
+                && has_special(target, 2)
+                && !is_filetype(target, FileType::Directory)
+                && !is_filetype(target, FileType::Unknown)
+        },
+    },
+    Rule {
+        id: "setid-on-world-writable",
+        severity: Severity::Error,
+        explanation: "anyone can replace the content of a file that runs with the privileges of its owner or group",
+        // setgid on a directory only passes its group on to new entries
+        check: |target| {
+            target.permission.other.write
+                && !is_filetype(target, FileType::Directory)
+                && (has_special(target, 0) || has_special(target, 1))
+        },
+    },
+    Rule {
+        id: "setuid-without-execute",
+        severity: Severity::Warning,
+        explanation: "the setuid bit is set but the owner can't execute it (`S`), which is most likely a mistake",
+        check: |target| has_special(target, 0) && !target.permission.user.execute,
+    },
+    Rule {
+        id: "writable-private-key",
+        severity: Severity::Error,
+        explanation: "the group or others can overwrite a private key",
+        check: |target| {
+            let permission = target.permission;
+            target.path.is_some_and(is_private_key_path)
+                && (permission.group.write || permission.other.write)
+        },
+    },
+    Rule {
+        id: "sticky-regular-file",
+        severity: Severity::Info,
+        explanation: "the sticky bit has no effect on regular files on Linux",
+        check: |target| has_special(target, 2) && is_filetype(target, FileType::Regular),
+    },
+    Rule {
+        id: "directory-without-owner-execute",
+        severity: Severity::Warning,
+        explanation: "the owner can't access the entries of the directory without execute (search) permission",
+        check: |target| {
+            is_filetype(target, FileType::Directory) && !target.permission.user.execute
+        },
+    },
+];
+
+/// Checks `permission` (of the file at `path`, if known) against all
+/// [`RULES`]. Symbolic links are skipped as their permission is never used.
+///
+/// ```rust
+/// use std::path::Path;
+/// use permcon::lint::{lint, Severity};
+/// use permcon::FilePermission;
+///
+/// let perm = FilePermission::try_from("drwxrwxrwx").unwrap();
+/// let findings = lint(&perm, None);
+///
+/// assert_eq!(findings[0].id, "world-writable-without-sticky");
+/// assert_eq!(findings[0].severity, Severity::Error);
+///
+/// let perm = FilePermission::try_from("-rw-rw----").unwrap();
+/// assert_eq!(lint(&perm, Some(Path::new("/home/joe/.ssh/id_ed25519")))[0].id, "writable-private-key");
+/// ```
+pub fn lint(permission: &FilePermission, path: Option<&Path>) -> Vec<Finding> {
+    if permission.filetype == FileType::Symlink {
+        return Vec::new();
+    }
+
+    let target = LintTarget { permission, path };
+
+    RULES
+        .iter()
+        .filter(|rule| (rule.check)(&target))
+        .map(|rule| Finding {
+            id: rule.id,
+            severity: rule.severity,
+            explanation: rule.explanation,
+        })
+        .collect()
+}
+
+/// Checks an entry of an archive (e.g., a release tarball) at `path`: all
+/// [`RULES`] plus setuid/setgid bits on files, which are worth a second look
+/// before publishing.
+///
+/// ```rust
+/// use std::path::Path;
+/// use permcon::lint::{lint_archive_entry, Severity};
+/// use permcon::FilePermission;
+///
+/// let perm = FilePermission::try_from("-rwsr-xr-x").unwrap();
+/// let findings = lint_archive_entry(&perm, Path::new("release/bin/run"));
+///
+/// assert_eq!(findings[0].id, "setid-file");
+/// assert_eq!(findings[0].severity, Severity::Info);
+/// ```
+pub fn lint_archive_entry(permission: &FilePermission, path: &Path) -> Vec<Finding> {
+    let mut findings = lint(permission, Some(path));
+    let target = LintTarget {
+        permission,
+        path: Some(path),
+    };
+
+    if !is_filetype(&target, FileType::Directory)
+        && !is_filetype(&target, FileType::Symlink)
+        && (has_special(&target, 0) || has_special(&target, 1))
+    {
+        findings.push(Finding {
+            id: "setid-file",
+            severity: Severity::Info,
+            explanation: "it runs with the privileges of its owner or group, make sure it has to",
+        });
+    }
+
+    findings
+}
+
+fn has_special(target: &LintTarget, index: usize) -> bool {
+    target.permission.special[index] != SpecialPermission::Nil
+}
+
+/// Permissions parsed from octal notation have an unknown file type and
+/// never match a known one.
+fn is_filetype(target: &LintTarget, filetype: FileType) -> bool {
+    target.permission.filetype == filetype
+}
+
+fn is_private_key_path(path: &Path) -> bool {
+    let name = match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name,
+        None => return false,
+    };
+
+    PRIVATE_KEY_NAMES.iter().any(|key_name| {
+        if key_name.starts_with('.') {
+            name.ends_with(key_name)
+        } else {
+            name == *key_name
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lint_ids(permission: &str, path: Option<&str>) -> Vec<&'static str> {
+        let permission = FilePermission::try_from(permission).unwrap();
+
+        lint(&permission, path.map(Path::new))
+            .iter()
+            .map(|finding| finding.id)
+            .collect()
+    }
+
+    #[test]
+    fn test_rules() {
+        let test_cases: [(&str, Option<&str>, &[&str]); 15] = [
+            ("-rw-r--r--", None, &[]),
+            ("drwxrwxrwt", None, &[]),
+            ("drwxrwsrwt", None, &[]),
+            ("0777", None, &["world-writable-without-sticky"]),
+            ("1777", None, &[]),
+            (
+                "-rw-rw-rwT",
+                None,
+                &["world-writable-file", "sticky-regular-file"],
+            ),
+            (
+                "-rwsrwxrwx",
+                None,
+                &["world-writable-without-sticky", "setid-on-world-writable"],
+            ),
+            (
+                "-rwxr-srwt",
+                None,
+                &[
+                    "world-writable-file",
+                    "setid-on-world-writable",
+                    "sticky-regular-file",
+                ],
+            ),
+            ("-rwSr--r--", None, &["setuid-without-execute"]),
+            (
+                "-rw-rw----",
+                Some("keys/server.key"),
+                &["writable-private-key"],
+            ),
+            ("-rw-r-----", Some("keys/server.key"), &[]),
+            ("-rw-rw----", Some(".ssh/id_rsa.pub"), &[]),
+            ("1644", None, &[]),
+            ("drw-r-xr-x", None, &["directory-without-owner-execute"]),
+            ("lrwxrwxrwx", None, &[]),
+        ];
+
+        for (permission, path, ids) in test_cases {
+            assert_eq!(lint_ids(permission, path), ids, "{permission}");
+        }
+    }
+
+    #[test]
+    fn test_severity() {
+        assert!(Severity::Info < Severity::Warning && Severity::Warning < Severity::Error);
+
+        for severity in [Severity::Info, Severity::Warning, Severity::Error] {
+            assert_eq!(Severity::from_str(&severity.to_string()), Ok(severity));
+        }
+
+        assert!(Severity::from_str("critical").is_err());
+    }
+}
@@ -0,0 +1,267 @@
+use crate::{error::PermconError, filetype::FileType, perm::FilePermission, symbolic::Symbolic};
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+lazy_static! {
+    /// A pattern to parse the columns of an `ls -l` line that follow the mode.
+    /// The date is either the default `Oct 17 10:00` / `Oct 17  2025` or an
+    /// ISO one (`--time-style=long-iso` or `full-iso`).
+    pub static ref LS_COLUMNS_PATTERN: Regex = Regex::new(
+        r"(?x)^
+        \s+ (?P<links>\d+)
+        \s+ (?P<owner>\S+)
+        \s+ (?P<group>\S+)
+        \s+ (?P<size>\d+,\s*\d+|\S+)
+        \s+ (?P<date>
+            \d{4}-\d{2}-\d{2} \s+ \d{2}:\d{2} (?::\d{2}(?:\.\d+)? \s+ [+-]\d{4})?
+            | \S+ \s+ \d{1,2} \s+ (?:\d{1,2}:\d{2}|\d{4})
+        )
+        \s (?P<name>.+)
+        $"
+    )
+    .unwrap();
+}
+
+/// The char `ls -l` prints right after the mode.
+#[derive(Debug, PartialEq, Eq, Serialize, Deserialize, Clone, Copy)]
+pub enum LsAttribute {
+    /// `+`: the file has an access control list.
+    Acl,
+    /// `.`: the file has an SELinux security context.
+    SecurityContext,
+    /// `@`: the file has extended attributes (macOS).
+    ExtendedAttributes,
+}
+
+impl LsAttribute {
+    /// Returns the attribute of a char, `None` if it isn't `+`, `.` or `@`.
+    pub const fn from_char(ch: char) -> Option<Self> {
+        match ch {
+            '+' => Some(LsAttribute::Acl),
+            '.' => Some(LsAttribute::SecurityContext),
+            '@' => Some(LsAttribute::ExtendedAttributes),
+            _ => None,
+        }
+    }
+
+    /// Returns the char `ls -l` prints for the attribute.
+    pub const fn to_char(self) -> char {
+        match self {
+            LsAttribute::Acl => '+',
+            LsAttribute::SecurityContext => '.',
+            LsAttribute::ExtendedAttributes => '@',
+        }
+    }
+
+    /// Splits the attribute char off an `ls -l` mode with a file type (e.g.,
+    /// `-rwxrwxr-x+`). Any other string is returned as is.
+    ///
+    /// ```rust
+    /// use permcon::ls::LsAttribute;
+    ///
+    /// assert_eq!(LsAttribute::split_mode("-rwxrwxr-x+"), ("-rwxrwxr-x", Some(LsAttribute::Acl)));
+    /// assert_eq!(LsAttribute::split_mode("rwxrwxr-x+"), ("rwxrwxr-x+", None));
+    /// ```
+    pub fn split_mode(mode: &str) -> (&str, Option<Self>) {
+        match mode.chars().last().and_then(LsAttribute::from_char) {
+            Some(attribute) if mode.chars().count() == 11 => {
+                (&mode[..mode.len() - 1], Some(attribute))
+            }
+            _ => (mode, None),
+        }
+    }
+}
+
+impl fmt::Display for LsAttribute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LsAttribute::Acl => "Access Control List",
+            LsAttribute::SecurityContext => "Security Context",
+            LsAttribute::ExtendedAttributes => "Extended Attributes",
+        };
+
+        write!(f, "{} ({})", name, self.to_char())
+    }
+}
+
+/// Represents a parsed `ls -l` line.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::ls::{LsAttribute, LsEntry};
+///
+/// let entry = LsEntry::from_str(
+///     "lrwxrwxrwx@ 1 root wheel 11 Oct 17  2025 bin -> usr/bin",
+/// ).unwrap();
+///
+/// assert_eq!(entry.permission.to_octal_str(), "0777");
+/// assert_eq!(entry.attribute, Some(LsAttribute::ExtendedAttributes));
+/// assert_eq!(entry.owner, "root");
+/// assert_eq!(entry.date, "Oct 17  2025");
+/// assert_eq!(entry.name, "bin");
+/// assert_eq!(entry.target.as_deref(), Some("usr/bin"));
+/// ```
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct LsEntry {
+    pub permission: FilePermission,
+    pub attribute: Option<LsAttribute>,
+    pub links: u64,
+    pub owner: String,
+    pub group: String,
+    /// The size as printed, e.g., `4096`, `4.0K` (`-h`) or `1, 3` (the
+    /// major and minor numbers of a device).
+    pub size: String,
+    /// The date as printed, e.g., `Oct 17 10:00`.
+    pub date: String,
+    pub name: String,
+    /// The target of a symbolic link (`name -> target`).
+    pub target: Option<String>,
+}
+
+impl LsEntry {
+    /// Returns a serialized JSON string. If `pretty` is `true` then beautifies
+    /// the JSON string.
+    pub fn to_json(&self, pretty: bool) -> String {
+        if pretty {
+            return serde_json::to_string_pretty(self).unwrap();
+        }
+
+        serde_json::to_string(self).unwrap()
+    }
+}
+
+impl FromStr for LsEntry {
+    type Err = PermconError;
+
+    /// Tries to parse a line printed by `ls -l` or `ls -la`.
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let mode_length = line.find(char::is_whitespace).unwrap_or(line.len());
+        let (mode, columns) = line.split_at(mode_length);
+
+        let (mode, attribute) = LsAttribute::split_mode(mode);
+
+        // the mode column always starts with the file type
+        if mode.chars().count() == 9 {
+            return Err(PermconError::InvalidLength {
+                input: mode.to_string(),
+                expected: vec![10, 11],
+                found: 9,
+            });
+        }
+
+        let symbolic = Symbolic::from_str(mode)?;
+
+        let invalid_line = || PermconError::InvalidLsLine {
+            input: line.to_string(),
+        };
+
+        let caps = LS_COLUMNS_PATTERN
+            .captures(columns)
+            .ok_or_else(invalid_line)?;
+
+        let column = |name: &str| caps.name(name).unwrap().as_str().to_string();
+        let permission = FilePermission::from(symbolic);
+
+        let (name, target) = match column("name").split_once(" -> ") {
+            Some((name, target)) if permission.filetype == FileType::Symlink => {
+                (name.to_string(), Some(target.to_string()))
+            }
+            _ => (column("name"), None),
+        };
+
+        Ok(LsEntry {
+            permission,
+            attribute,
+            links: column("links").parse().map_err(|_| invalid_line())?,
+            owner: column("owner"),
+            group: column("group"),
+            size: column("size"),
+            date: column("date"),
+            name,
+            target,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ls_lines() {
+        // (line, octal, attribute, size, date, name, target)
+        let test_cases = [
+            (
+                "-rw-r--r--  1 sifat users 1234 Oct 17 10:00 notes with spaces.txt",
+                "0644",
+                None,
+                "1234",
+                "Oct 17 10:00",
+                "notes with spaces.txt",
+                None,
+            ),
+            (
+                "drwxrwxrwt+ 12 root root 4.0K Jan  3  2025 tmp",
+                "1777",
+                Some(LsAttribute::Acl),
+                "4.0K",
+                "Jan  3  2025",
+                "tmp",
+                None,
+            ),
+            (
+                "crw-rw-rw-.  1 root root   1,   3 Oct 17 06:38 null",
+                "0666",
+                Some(LsAttribute::SecurityContext),
+                "1,   3",
+                "Oct 17 06:38",
+                "null",
+                None,
+            ),
+            (
+                "lrwxrwxrwx  1 root root 7 2025-05-20 00:00:00.000000000 +0000 bin -> usr/bin",
+                "0777",
+                None,
+                "7",
+                "2025-05-20 00:00:00.000000000 +0000",
+                "bin",
+                Some("usr/bin"),
+            ),
+            (
+                "-rwsr-xr-x 1 0 0 55680 2024-02-01 12:30 a -> b",
+                "4755",
+                None,
+                "55680",
+                "2024-02-01 12:30",
+                "a -> b",
+                None,
+            ),
+        ];
+
+        for (line, octal, attribute, size, date, name, target) in test_cases {
+            let entry = LsEntry::from_str(line).unwrap();
+
+            assert_eq!(entry.permission.to_octal_str(), octal, "{line}");
+            assert_eq!(entry.attribute, attribute, "{line}");
+            assert_eq!(entry.size, size, "{line}");
+            assert_eq!(entry.date, date, "{line}");
+            assert_eq!(entry.name, name, "{line}");
+            assert_eq!(entry.target.as_deref(), target, "{line}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_ls_lines() {
+        for line in [
+            "total 8",
+            "rwxr-xr-x 1 root root 0 Oct 17 10:00 file",
+            "-rwxr-xr-z 1 root root 0 Oct 17 10:00 file",
+            "-rwxr-xr-x root root 0 Oct 17 10:00 file",
+            "-rwxr-xr-x",
+        ] {
+            assert!(LsEntry::from_str(line).is_err(), "{line}");
+        }
+    }
+}
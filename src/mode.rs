@@ -0,0 +1,333 @@
+use crate::{
+    error::PermconError, filetype::FileType, octal::Octal, perm::FilePermission, symbolic::Symbolic,
+};
+use std::{
+    fmt,
+    ops::{BitAnd, BitOr, Not, Sub},
+    str::FromStr,
+};
+
+/// A compact, `Copy` representation of the raw `st_mode` bits (file type,
+/// special and permission bits) for when a `FilePermission` is too heavy.
+///
+/// ```rust
+/// use permcon::{FilePermission, Mode};
+///
+/// const SCRIPT: Mode = Mode::new(0o100755);
+///
+/// let mode = Mode::S_IFREG | Mode::S_IRWXU | Mode::S_IRGRP | Mode::S_IXGRP
+///     | Mode::S_IROTH | Mode::S_IXOTH;
+/// assert_eq!(mode, SCRIPT);
+/// assert_eq!(mode - Mode::S_IXOTH, Mode::new(0o100754));
+/// assert!(mode.contains(Mode::S_IRUSR | Mode::S_IXUSR));
+///
+/// let perm = FilePermission::try_from(mode).unwrap();
+/// assert_eq!(perm.to_symbolic_str(), "-rwxr-xr-x");
+/// assert_eq!(Mode::from(&perm), mode);
+///
+/// // `FileType` has no variant for these file type bits
+/// assert!(FilePermission::try_from(Mode::new(0o170644)).is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Mode(u32);
+
+impl Mode {
+    /// Mask of the file type bits.
+    pub const S_IFMT: Mode = Mode(0o170000);
+    pub const S_IFSOCK: Mode = Mode(0o140000);
+    pub const S_IFLNK: Mode = Mode(0o120000);
+    pub const S_IFREG: Mode = Mode(0o100000);
+    pub const S_IFBLK: Mode = Mode(0o060000);
+    pub const S_IFDIR: Mode = Mode(0o040000);
+    pub const S_IFCHR: Mode = Mode(0o020000);
+    pub const S_IFIFO: Mode = Mode(0o010000);
+
+    pub const S_ISUID: Mode = Mode(0o4000);
+    pub const S_ISGID: Mode = Mode(0o2000);
+    pub const S_ISVTX: Mode = Mode(0o1000);
+
+    pub const S_IRWXU: Mode = Mode(0o700);
+    pub const S_IRUSR: Mode = Mode(0o400);
+    pub const S_IWUSR: Mode = Mode(0o200);
+    pub const S_IXUSR: Mode = Mode(0o100);
+
+    pub const S_IRWXG: Mode = Mode(0o070);
+    pub const S_IRGRP: Mode = Mode(0o040);
+    pub const S_IWGRP: Mode = Mode(0o020);
+    pub const S_IXGRP: Mode = Mode(0o010);
+
+    pub const S_IRWXO: Mode = Mode(0o007);
+    pub const S_IROTH: Mode = Mode(0o004);
+    pub const S_IWOTH: Mode = Mode(0o002);
+    pub const S_IXOTH: Mode = Mode(0o001);
+
+    /// Creates a `Mode` from raw `st_mode` bits (e.g., `0o100644`).
+    pub const fn new(bits: u32) -> Self {
+        Mode(bits)
+    }
+
+    /// Creates a `Mode` from a symbolic file type char (e.g., `'d'`) and the
+    /// permission and special bits. Unknown file type chars leave the file
+    /// type bits empty.
+    pub const fn from_filetype_char(filetype_char: char, permission_bits: u16) -> Self {
+        Mode::from_filetype(FileType::from_char(filetype_char), permission_bits)
+    }
+
+    /// Creates a `Mode` from a file type and the permission and special bits.
+    /// `FileType::Unknown` leaves the file type bits empty.
+    pub const fn from_filetype(filetype: FileType, permission_bits: u16) -> Self {
+        Mode(filetype.to_mode_bits() | (permission_bits as u32 & 0o7777))
+    }
+
+    /// Creates a `Mode` from an `Octal`. An `Unknown` file type leaves the
+    /// file type bits empty.
+    pub const fn from_octal(octal: &Octal) -> Self {
+        Mode(
+            octal.filetype.to_mode_bits()
+                | (octal.special as u32 & 0o7) << 9
+                | (octal.user as u32 & 0o7) << 6
+                | (octal.group as u32 & 0o7) << 3
+                | (octal.other as u32 & 0o7),
+        )
+    }
+
+    /// Returns the raw bits.
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    /// Returns the permission and special bits (e.g., `0o1755`).
+    pub const fn permission_bits(self) -> u16 {
+        (self.0 & 0o7777) as u16
+    }
+
+    /// Returns the file type bits (e.g., `0o040000` for a directory).
+    pub const fn filetype_bits(self) -> u32 {
+        self.0 & Self::S_IFMT.0
+    }
+
+    /// Returns the file type. A mode without file type bits has an
+    /// `Unknown` file type, like a permission parsed from octal notation.
+    pub const fn filetype(self) -> FileType {
+        FileType::from_mode(self.0)
+    }
+
+    /// Returns the symbolic file type char. A mode without file type bits
+    /// maps to `'-'`, like in [`FilePermission::to_symbolic_str`].
+    pub const fn filetype_char(self) -> char {
+        self.filetype().to_char()
+    }
+
+    /// Returns `self` if it converts to a `FilePermission`, `Octal` or
+    /// `Symbolic` and back without a loss, i.e., it has no bits above
+    /// `0o177777` and its file type bits, if any, are of a known file type.
+    pub fn checked(self) -> Result<Self, PermconError> {
+        if self.0 > 0o177777 || (self.filetype_bits() != 0 && self.filetype() == FileType::Unknown)
+        {
+            return Err(PermconError::InvalidValue {
+                input: format!("{:06o}", self.0),
+                expected: String::from("an st_mode within 0..=177777 with a known file type"),
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// Returns the file type, special and permission bits as an `Octal`.
+    /// Unrecognized file type bits are lost (see [`Mode::checked`]).
+    pub const fn to_octal(self) -> Octal {
+        let bits = self.0;
+
+        Octal {
+            filetype: self.filetype(),
+            special: ((bits >> 9) & 0o7) as u8,
+            user: ((bits >> 6) & 0o7) as u8,
+            group: ((bits >> 3) & 0o7) as u8,
+            other: (bits & 0o7) as u8,
+        }
+    }
+
+    /// Returns `true` if all bits of `other` are set in `self`.
+    pub const fn contains(self, other: Mode) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for Mode {
+    type Output = Mode;
+
+    fn bitor(self, rhs: Mode) -> Mode {
+        Mode(self.0 | rhs.0)
+    }
+}
+
+impl BitAnd for Mode {
+    type Output = Mode;
+
+    fn bitand(self, rhs: Mode) -> Mode {
+        Mode(self.0 & rhs.0)
+    }
+}
+
+impl Not for Mode {
+    type Output = Mode;
+
+    fn not(self) -> Mode {
+        Mode(!self.0)
+    }
+}
+
+impl Sub for Mode {
+    type Output = Mode;
+
+    /// Removes the bits of `rhs` from `self`.
+    fn sub(self, rhs: Mode) -> Mode {
+        Mode(self.0 & !rhs.0)
+    }
+}
+
+impl fmt::Octal for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Octal::fmt(&self.0, f)
+    }
+}
+
+impl From<&FilePermission> for Mode {
+    /// A permission with an `Unknown` file type (e.g., parsed from octal
+    /// notation) leaves the file type bits empty.
+    fn from(permission: &FilePermission) -> Self {
+        Mode::from_filetype(permission.filetype, permission.to_bits())
+    }
+}
+
+impl TryFrom<Mode> for FilePermission {
+    type Error = PermconError;
+
+    /// Fails if the conversion would lose bits, see [`Mode::checked`]. The
+    /// source format of the resulting permission is left empty.
+    fn try_from(mode: Mode) -> Result<Self, Self::Error> {
+        let mode = mode.checked()?;
+        Ok(FilePermission::from_bits(
+            mode.permission_bits(),
+            mode.filetype(),
+        ))
+    }
+}
+
+impl From<Octal> for Mode {
+    fn from(octal: Octal) -> Self {
+        Mode::from_octal(&octal)
+    }
+}
+
+impl TryFrom<Mode> for Octal {
+    type Error = PermconError;
+
+    /// Fails if the conversion would lose bits, see [`Mode::checked`].
+    fn try_from(mode: Mode) -> Result<Self, Self::Error> {
+        Ok(mode.checked()?.to_octal())
+    }
+}
+
+impl From<Symbolic> for Mode {
+    fn from(symbolic: Symbolic) -> Self {
+        Mode::from(&FilePermission::from(symbolic))
+    }
+}
+
+impl TryFrom<Mode> for Symbolic {
+    type Error = PermconError;
+
+    /// Fails if the conversion would lose bits, see [`Mode::checked`].
+    fn try_from(mode: Mode) -> Result<Self, Self::Error> {
+        let [user, group, other] = FilePermission::try_from(mode)?.to_symbolic_bits_arr();
+
+        Ok(Symbolic {
+            filetype: mode.filetype_char(),
+            user,
+            group,
+            other,
+        })
+    }
+}
+
+impl FromStr for Mode {
+    type Err = PermconError;
+
+    /// Parses a symbolic or an octal permission, see `FilePermission::try_from`.
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        Ok(Mode::from(&FilePermission::try_from(mode)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILETYPE_CHARS: [char; 7] = ['-', 'd', 'l', 'b', 'c', 's', 'p'];
+
+    #[test]
+    fn test_conversions_are_lossless() {
+        for bits in 0..=0o7777u16 {
+            let octal_mode = Mode::new(bits as u32);
+            let octal = octal_mode.to_octal();
+            let permission = FilePermission::try_from(octal_mode).unwrap();
+
+            assert_eq!(Mode::from(&permission), octal_mode);
+            assert_eq!(permission.filetype, FileType::Unknown);
+            assert_eq!(Mode::from_octal(&octal), octal_mode);
+            assert_eq!(
+                Mode::from(&FilePermission::from(Octal::try_from(octal_mode).unwrap())),
+                octal_mode
+            );
+
+            for filetype_char in FILETYPE_CHARS {
+                let mode = Mode::from_filetype_char(filetype_char, bits);
+                let permission = FilePermission::try_from(mode).unwrap();
+
+                assert_eq!(
+                    permission,
+                    FilePermission::from_bits(bits, FileType::from_char(filetype_char))
+                );
+                assert_eq!(Mode::from(&permission), mode);
+
+                let symbolic = Symbolic::try_from(mode).unwrap();
+                assert_eq!(
+                    Symbolic::from_str(&permission.to_symbolic_str()).as_ref(),
+                    Ok(&symbolic)
+                );
+                assert_eq!(Mode::from(symbolic), mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lossy_modes_are_rejected() {
+        // an unknown file type, and bits above `S_IFMT`
+        for bits in [0o170644, 0o1100644] {
+            let mode = Mode::new(bits);
+
+            assert!(mode.checked().is_err(), "{bits:o}");
+            assert!(FilePermission::try_from(mode).is_err(), "{bits:o}");
+            assert!(Octal::try_from(mode).is_err(), "{bits:o}");
+            assert!(Symbolic::try_from(mode).is_err(), "{bits:o}");
+        }
+
+        assert_eq!(Mode::new(0o100644).checked(), Ok(Mode::new(0o100644)));
+    }
+
+    #[test]
+    fn test_operators() {
+        let mode = Mode::S_IFDIR | Mode::S_IRWXU | Mode::S_IRGRP | Mode::S_ISVTX;
+
+        assert_eq!(mode, Mode::new(0o041740));
+        assert_eq!(mode.filetype_char(), 'd');
+        assert_eq!(mode & Mode::S_IFMT, Mode::S_IFDIR);
+        assert_eq!(mode & !Mode::S_IRWXU, Mode::new(0o041040));
+        assert_eq!(mode - Mode::S_IFMT - Mode::S_ISVTX, Mode::new(0o740));
+        assert_eq!(format!("{:o}", mode), "41740");
+        assert_eq!(Mode::from_str("drwxr-xr-x"), Ok(Mode::new(0o40755)));
+        assert_eq!(Mode::from_str("1755"), Ok(Mode::new(0o1755)));
+        assert!(!mode.contains(Mode::S_IWGRP));
+    }
+}
@@ -1,6 +1,8 @@
+use crate::{error::PermconError, filetype::FileType, mode::Mode};
 #[allow(unused)]
 use lazy_static::lazy_static;
 use regex::Regex;
+use std::str::FromStr;
 
 const OCTAL_PATTERN_GROUPS: [&str; 4] = ["special", "user", "group", "other"];
 
@@ -11,25 +13,47 @@ lazy_static! {
     pub static ref OCTAL_PATTERN: Regex =
         Regex::new(r"(?x)^(?P<special>[0-7])?(?P<user>[0-7])(?P<group>[0-7])(?P<other>[0-7])$")
             .unwrap();
+
+    /// A pattern to parse a full `st_mode` in octal notation (e.g., `100644`
+    /// or `0120777`), as printed by git, tar or Python's `oct(os.stat().st_mode)`.
+    pub static ref FULL_MODE_PATTERN: Regex = Regex::new(r"^[0-7]{5,7}$").unwrap();
+
+    /// A pattern to parse a full `st_mode` in hexadecimal notation (e.g.,
+    /// `81a4` of `stat -c %f`, or `0x81a4`). Without the `0x` prefix, a
+    /// number must also have a letter to be taken as hexadecimal.
+    pub static ref HEX_MODE_PATTERN: Regex =
+        Regex::new(r"^(?:0[xX](?P<prefixed>[0-9a-fA-F]{1,4})|(?P<bare>[0-9a-fA-F]{4}))$").unwrap();
 }
 
 /// Represents a parsed octal permission.
 ///
+/// Besides the 3 or 4 digit permission, a full `st_mode` (5 to 7 octal
+/// digits, or 4 hexadecimal ones) is accepted and its `S_IFMT` bits are
+/// decoded into the file type.
+///
 /// ``` rust
+/// use std::str::FromStr;
 /// use permcon::octal::Octal;
+/// use permcon::FileType;
 ///
 /// let perm = "1641";
 ///
 /// assert!(Octal::is_valid(perm));
 /// assert_eq!(Octal::from_str(perm).unwrap(), Octal {
+///     filetype: FileType::Unknown,
 ///     special: 1,
 ///     user: 6,
 ///     group: 4,
 ///     other: 1,
 /// });
+///
+/// assert_eq!(Octal::from_str("040755").unwrap().filetype, FileType::Directory);
+/// assert_eq!(Octal::from_str("81a4").unwrap(), Octal::from_str("100644").unwrap());
 /// ```
 #[derive(Debug, PartialEq, Eq)]
 pub struct Octal {
+    /// `Unknown` unless parsed from a full `st_mode`.
+    pub filetype: FileType,
     pub special: u8,
     pub user: u8,
     pub group: u8,
@@ -40,13 +64,20 @@ impl Octal {
     /// Checks whether the given permission string is a valid permission in the
     /// octal notation.
     pub fn is_valid(permission: &str) -> bool {
-        return OCTAL_PATTERN.is_match(permission);
+        OCTAL_PATTERN.is_match(permission)
     }
+}
+
+impl FromStr for Octal {
+    type Err = PermconError;
 
     /// Tries to parse the permission string into the Octal struct
-    pub fn from_str(permission: &str) -> Result<Self, String> {
+    fn from_str(permission: &str) -> Result<Self, Self::Err> {
         if !Self::is_valid(permission) {
-            return Err(String::from("Invalid octal permission."));
+            return match parse_full_mode(permission) {
+                Some(result) => result.map(|mode| mode.to_octal()),
+                None => Err(find_error(permission)),
+            };
         }
 
         let caps = OCTAL_PATTERN.captures(permission).expect(
@@ -59,6 +90,7 @@ impl Octal {
         });
 
         Ok(Octal {
+            filetype: FileType::Unknown,
             user,
             group,
             other,
@@ -66,3 +98,162 @@ impl Octal {
         })
     }
 }
+
+/// Parses a full `st_mode` in octal or hexadecimal notation. Returns `None`
+/// if `permission` doesn't look like one, e.g., a bare hexadecimal number
+/// without a letter (`8644`, most likely a typo of an octal one) or without
+/// file type bits is left to the octal error reporting.
+fn parse_full_mode(permission: &str) -> Option<Result<Mode, PermconError>> {
+    let bits = if FULL_MODE_PATTERN.is_match(permission) {
+        u32::from_str_radix(permission, 8).unwrap()
+    } else {
+        let caps = HEX_MODE_PATTERN.captures(permission)?;
+        let digits = caps.name("prefixed").or(caps.name("bare")).unwrap();
+        let bits = u32::from_str_radix(digits.as_str(), 16).unwrap();
+
+        let is_bare = caps.name("bare").is_some();
+
+        if is_bare
+            && (digits.as_str().chars().all(|ch| ch.is_ascii_digit())
+                || bits & Mode::S_IFMT.bits() == 0)
+        {
+            return None;
+        }
+
+        bits
+    };
+
+    let filetype_bits = bits & Mode::S_IFMT.bits();
+
+    if bits > 0o177777 || (filetype_bits != 0 && FileType::from_mode(bits) == FileType::Unknown) {
+        return Some(Err(PermconError::InvalidValue {
+            input: permission.to_string(),
+            expected: String::from("an st_mode within 0..=177777 with a known file type"),
+        }));
+    }
+
+    Some(Ok(Mode::new(bits)))
+}
+
+/// Finds out why `permission` doesn't match the `OCTAL_PATTERN`.
+fn find_error(permission: &str) -> PermconError {
+    let input = permission.to_string();
+    let length = permission.chars().count();
+
+    if !(3..=7).contains(&length) {
+        return PermconError::InvalidLength {
+            input,
+            expected: vec![3, 4, 5, 6, 7],
+            found: length,
+        };
+    }
+
+    let (position, found) = permission
+        .chars()
+        .enumerate()
+        .find(|(_, ch)| !ch.is_digit(8))
+        .expect("The permission must have an invalid char as it didn't match the pattern.");
+
+    match found.to_digit(10) {
+        Some(digit) => PermconError::OctalDigitOutOfRange {
+            input,
+            position,
+            digit: digit as u8,
+        },
+        None => PermconError::InvalidChar {
+            input,
+            position,
+            found,
+            expected: String::from("an octal digit (0-7)"),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let test_cases = [
+            (
+                "75",
+                PermconError::InvalidLength {
+                    input: String::from("75"),
+                    expected: vec![3, 4, 5, 6, 7],
+                    found: 2,
+                },
+            ),
+            (
+                "0759",
+                PermconError::OctalDigitOutOfRange {
+                    input: String::from("0759"),
+                    position: 3,
+                    digit: 9,
+                },
+            ),
+            (
+                "8644",
+                PermconError::OctalDigitOutOfRange {
+                    input: String::from("8644"),
+                    position: 0,
+                    digit: 8,
+                },
+            ),
+            (
+                "9999",
+                PermconError::OctalDigitOutOfRange {
+                    input: String::from("9999"),
+                    position: 0,
+                    digit: 9,
+                },
+            ),
+            (
+                "7a5",
+                PermconError::InvalidChar {
+                    input: String::from("7a5"),
+                    position: 1,
+                    found: 'a',
+                    expected: String::from("an octal digit (0-7)"),
+                },
+            ),
+        ];
+
+        for (permission, error) in test_cases {
+            assert_eq!(Octal::from_str(permission), Err(error), "{permission}");
+        }
+    }
+
+    #[test]
+    fn test_parse_full_modes() {
+        // (mode, file type, permission bits)
+        let test_cases: [(&str, FileType, u16); 7] = [
+            ("100644", FileType::Regular, 0o644),
+            ("0120777", FileType::Symlink, 0o777),
+            ("41777", FileType::Directory, 0o1777),
+            ("81a4", FileType::Regular, 0o644),
+            ("0xA1FF", FileType::Symlink, 0o777),
+            ("0x1ed", FileType::Unknown, 0o755),
+            ("00755", FileType::Unknown, 0o755),
+        ];
+
+        for (mode, filetype, bits) in test_cases {
+            let octal = Octal::from_str(mode).unwrap();
+
+            assert_eq!(octal.filetype, filetype, "{mode}");
+            assert_eq!(Mode::from_octal(&octal).permission_bits(), bits, "{mode}");
+        }
+
+        for mode in ["170644", "1234567", "0x12345"] {
+            assert!(Octal::from_str(mode).is_err(), "{mode}");
+        }
+
+        for filetype in FileType::ALL {
+            let mode = Mode::from_filetype(filetype, 0o4755);
+
+            for text in [format!("{:06o}", mode), format!("{:#x}", mode.bits())] {
+                assert_eq!(Octal::from_str(&text), Ok(mode.to_octal()), "{text}");
+            }
+        }
+    }
+}
@@ -1,19 +1,28 @@
 use crate::{
+    chmod::ChmodExpr,
+    error::PermconError,
+    filetype::FileType,
+    ls::LsAttribute,
+    mode::Mode,
     octal::Octal,
     symbolic::Symbolic,
-    utils::{
-        bool_arr_to_octal_digit, get_filetype_from_char, parse_octal_digit,
-        parse_symbolic_execution_bit,
-    },
+    utils::{bool_arr_to_octal_digit, parse_octal_digit, parse_symbolic_execution_bit},
 };
-use serde::{Serialize, Serializer};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{json, to_string_pretty};
 use std::str::FromStr;
 
 const SPECIAL_CHARS: [char; 3] = ['s', 's', 't'];
 const SPECIAL_PERMISSIONS_ORDER: [SpecialPermission; 3] = [SUID, SGID, StickyBit];
 
-#[derive(Debug, PartialEq, Serialize, Clone)]
+/// The allowed chars of the symbolic bits (`rwx`) and their description.
+const SYMBOLIC_BIT_CHARS: [(&str, &str); 3] = [
+    ("r-", "'r' or '-'"),
+    ("w-", "'w' or '-'"),
+    ("xsStT-", "one of 'x', 's', 'S', 't', 'T', '-'"),
+];
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum SpecialPermission {
     Nil,
     SGID,
@@ -25,36 +34,38 @@ use crate::perm::SpecialPermission::*;
 
 /// Represents the source notation (symbolic or octal) from which the FilePermission has been
 /// parsed.
-#[derive(Debug, PartialEq, Serialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub enum SourceFormat {
     Octal,
     Symbolic,
 }
 
+/// The JSON Schema of the serialized `FilePermission` (see
+/// [`FilePermission::to_json`]).
+pub const JSON_SCHEMA: &str = include_str!("../schema/file-permission.schema.json");
+
 /// Represents a parsed file permission and provides methods to convert the
 /// file permission to different notations.
-#[derive(Debug, Serialize, PartialEq)]
+///
+/// It (de)serializes through the JSON layout described by [`JSON_SCHEMA`],
+/// which also carries the `octal` and `symbolic` strings.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+#[serde(into = "FilePermissionJson", try_from = "FilePermissionJson")]
 pub struct FilePermission {
     pub user: GroupPermission,
     pub group: GroupPermission,
     pub other: GroupPermission,
 
-    pub filetype: String,
-
-    #[serde(skip_serializing)]
-    pub filetype_char: char,
-
-    #[serde(skip_serializing)]
+    pub filetype: FileType,
     pub source_format: Option<SourceFormat>,
-
-    #[serde(serialize_with = "serialize_special_permissions")]
     pub special: [SpecialPermission; 3],
 }
 
 impl FilePermission {
-    /// Serializes the `FilePermission` into symbolic notation.
+    /// Serializes the `FilePermission` into symbolic notation. Without a
+    /// known file type the first char is `-`.
     pub fn to_symbolic_str(&self) -> String {
-        self.filetype_char.to_string() + self.to_symbolic_bits_arr().join("").as_str()
+        self.filetype.to_char().to_string() + self.to_symbolic_bits_arr().join("").as_str()
     }
 
     /// Returns symbolic bin_str (e.g., `rwx`) (`[String; 3]`) array.
@@ -71,7 +82,7 @@ impl FilePermission {
     /// Serializes the `FilePermission` into octal notation.
     pub fn to_octal_str(&self) -> String {
         let special_digit = {
-            let special: &[bool; 3] = &self.special.clone().map(|val| (val != Nil));
+            let special: &[bool; 3] = &self.special.clone().map(|val| val != Nil);
             bool_arr_to_octal_digit(special).to_string()
         };
 
@@ -83,13 +94,96 @@ impl FilePermission {
         special_digit + &group_digits
     }
 
+    /// Serializes the `FilePermission` into a full `st_mode` in octal
+    /// notation (e.g., `100644`), including the file type bits. Without a
+    /// known file type it's the same as [`FilePermission::to_octal_str`].
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let perm = FilePermission::try_from("drwxr-xr-x").unwrap();
+    /// assert_eq!(perm.to_full_octal_str(), "040755");
+    ///
+    /// let perm = FilePermission::try_from("0120777").unwrap();
+    /// assert_eq!(perm.to_symbolic_str(), "lrwxrwxrwx");
+    /// assert_eq!(FilePermission::try_from("0755").unwrap().to_full_octal_str(), "0755");
+    /// ```
+    pub fn to_full_octal_str(&self) -> String {
+        if self.filetype == FileType::Unknown {
+            return self.to_octal_str();
+        }
+
+        format!("{:06o}", Mode::from(self))
+    }
+
+    /// Serializes the `FilePermission` into the shortest chmod expression of
+    /// `=` clauses that sets exactly these bits, whatever the bits were
+    /// before. Classes with the same permissions share a clause and special
+    /// bits are added at the end.
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let to_chmod_str = |perm| FilePermission::try_from(perm).unwrap().to_chmod_str();
+    ///
+    /// assert_eq!(to_chmod_str("0755"), "u=rwx,go=rx");
+    /// assert_eq!(to_chmod_str("0644"), "u=rw,go=r");
+    /// assert_eq!(to_chmod_str("4750"), "u=rwx,g=rx,o=,u+s");
+    /// assert_eq!(to_chmod_str("drwxrwxrwt"), "a=rwx,+t");
+    /// ```
+    pub fn to_chmod_str(&self) -> String {
+        let mut clauses: Vec<(String, String)> = Vec::new();
+
+        for (who, perm) in ['u', 'g', 'o'].into_iter().zip(self.to_perm_group_array()) {
+            let perms: String = ['r', 'w', 'x']
+                .into_iter()
+                .zip(perm.as_rwx_array())
+                .filter(|(_, is_set)| *is_set)
+                .map(|(char, _)| char)
+                .collect();
+
+            match clauses
+                .iter_mut()
+                .find(|(_, clause_perms)| *clause_perms == perms)
+            {
+                Some((clause_who, _)) => clause_who.push(who),
+                None => clauses.push((who.to_string(), perms)),
+            }
+        }
+
+        let mut clauses: Vec<String> = clauses
+            .into_iter()
+            .map(|(who, perms)| {
+                let who = if who == "ugo" { String::from("a") } else { who };
+                format!("{who}={perms}")
+            })
+            .collect();
+
+        let setid_who: String = ['u', 'g']
+            .into_iter()
+            .zip(&self.special)
+            .filter(|(_, special)| **special != Nil)
+            .map(|(who, _)| who)
+            .collect();
+
+        if !setid_who.is_empty() {
+            clauses.push(format!("{setid_who}+s"));
+        }
+
+        if self.special[2] != Nil {
+            clauses.push(String::from("+t"));
+        }
+
+        clauses.join(",")
+    }
+
     /// Returns `[&GroupPermission; 3]` as `[user, group, other]`
     pub fn to_perm_group_array(&self) -> [&GroupPermission; 3] {
         [&self.user, &self.group, &self.other]
     }
 
     /// Returns a serialized JSON string. If `pretty` is `true` then beautifies
-    /// the JSON string.
+    /// the JSON string. Read it back with [`FilePermission::from_json`].
     pub fn to_json(&self, pretty: bool) -> String {
         let perm_json = json!(&self);
 
@@ -99,6 +193,86 @@ impl FilePermission {
 
         perm_json.to_string()
     }
+
+    /// Parses a JSON string produced by [`FilePermission::to_json`].
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let perm = FilePermission::try_from("drwxr-xr-t").unwrap();
+    /// assert_eq!(FilePermission::from_json(&perm.to_json(false)).unwrap(), perm);
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+
+    /// Applies a chmod expression (e.g., `u+x,g-w,o=`) and returns the
+    /// resulting `FilePermission`. The file type is left untouched but decides
+    /// how the conditional execute (`X`) behaves: directories always get
+    /// execute, other files only if some execute bit is already set.
+    ///
+    /// ```rust
+    /// use permcon::FilePermission;
+    ///
+    /// let perm = FilePermission::try_from("-rw-r--r--").unwrap();
+    /// let perm = perm.apply("u+x,g+s").unwrap();
+    ///
+    /// assert_eq!(perm.to_symbolic_str(), "-rwxr-Sr--");
+    /// assert_eq!(perm.to_octal_str(), "2744");
+    ///
+    /// let dir = FilePermission::try_from("drw-r--r--").unwrap();
+    /// assert_eq!(dir.apply("a+X").unwrap().to_symbolic_str(), "drwxr-xr-x");
+    /// ```
+    pub fn apply(&self, expr: &str) -> Result<Self, PermconError> {
+        Ok(self.apply_expr(&ChmodExpr::from_str(expr)?))
+    }
+
+    /// Applies an already parsed `ChmodExpr`. See [`FilePermission::apply`].
+    pub fn apply_expr(&self, expr: &ChmodExpr) -> Self {
+        self.with_bits(expr.apply_to_bits(self.to_bits(), self.filetype == FileType::Directory))
+    }
+
+    /// Returns the permission and special bits (e.g., `0o1755`).
+    pub(crate) fn to_bits(&self) -> u16 {
+        let digit = |bits: [bool; 3]| {
+            bits.into_iter()
+                .fold(0, |digit, is_set| (digit << 1) | u16::from(is_set))
+        };
+        let special = self.special.each_ref().map(|perm| *perm != Nil);
+
+        digit(special) << 9
+            | digit(self.user.as_rwx_array()) << 6
+            | digit(self.group.as_rwx_array()) << 3
+            | digit(self.other.as_rwx_array())
+    }
+
+    /// Returns a copy of `self` with the permission and special bits replaced
+    /// by `bits`, keeping the file type and source format.
+    pub(crate) fn with_bits(&self, bits: u16) -> Self {
+        FilePermission {
+            source_format: self.source_format.clone(),
+            ..FilePermission::from_bits(bits, self.filetype)
+        }
+    }
+
+    /// Builds a `FilePermission` from the permission and special bits and a
+    /// file type. The source format is left empty.
+    pub(crate) fn from_bits(bits: u16, filetype: FileType) -> Self {
+        let digit = |shift: u16| ((bits >> shift) & 0o7) as u8;
+
+        let octal = Octal {
+            filetype,
+            special: digit(9),
+            user: digit(6),
+            group: digit(3),
+            other: digit(0),
+        };
+
+        FilePermission {
+            source_format: None,
+            ..FilePermission::from(octal)
+        }
+    }
 }
 
 fn get_special_perms_array<T, U>(source_array: &[T; 3], is_special: U) -> [SpecialPermission; 3]
@@ -128,9 +302,8 @@ impl From<Symbolic> for FilePermission {
             group,
             other,
             special: special_perms,
-            filetype_char: symbolic_perm.filetype,
+            filetype: FileType::from_char(symbolic_perm.filetype),
             source_format: Some(SourceFormat::Symbolic),
-            filetype: get_filetype_from_char(symbolic_perm.filetype),
         }
     }
 }
@@ -156,37 +329,50 @@ impl From<Octal> for FilePermission {
             user,
             group,
             other,
-            filetype_char: '-',
             special: special_perms,
-            filetype: get_filetype_from_char('0'),
+            filetype: octal_perm.filetype,
             source_format: Some(SourceFormat::Octal),
         }
     }
 }
 
 impl TryFrom<&str> for FilePermission {
-    type Error = String;
+    type Error = PermconError;
 
+    /// Tries to parse a symbolic or an octal permission. If both fail, the
+    /// error of the octal parser is returned for all-digit input and the one
+    /// of the symbolic parser otherwise.
+    ///
+    /// The `+`, `.` or `@` that `ls -l` prints after a mode (e.g.,
+    /// `-rwxrwxr-x+`) is ignored, see [`LsAttribute::split_mode`] to get it.
     fn try_from(perm_str: &str) -> Result<Self, Self::Error> {
-        if let Ok(symbolic) = Symbolic::from_str(perm_str) {
-            return Ok(FilePermission::from(symbolic));
-        }
+        let (perm_str, _) = LsAttribute::split_mode(perm_str);
 
-        if let Ok(octal) = Octal::from_str(perm_str) {
-            return Ok(FilePermission::from(octal));
-        }
+        let symbolic_error = match Symbolic::from_str(perm_str) {
+            Ok(symbolic) => return Ok(FilePermission::from(symbolic)),
+            Err(error) => error,
+        };
+
+        let octal_error = match Octal::from_str(perm_str) {
+            Ok(octal) => return Ok(FilePermission::from(octal)),
+            Err(error) => error,
+        };
 
-        return Err(format!("Invalid file permission: {perm_str}!"));
+        if !perm_str.is_empty() && perm_str.chars().all(|ch| ch.is_ascii_digit()) {
+            Err(octal_error)
+        } else {
+            Err(symbolic_error)
+        }
     }
 }
 
 /// Represents a parsed group (user, group and other) permission.
-#[derive(Debug, Serialize, PartialEq, Eq)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct GroupPermission {
     pub read: bool,
     pub write: bool,
     pub execute: bool,
-    #[serde(skip_serializing)]
+    #[serde(skip_serializing, default)]
     pub special: bool,
 }
 
@@ -208,21 +394,40 @@ impl GroupPermission {
     ///     }
     /// );
     /// ```
-    pub fn from_symbolic_bits(bits: &str) -> Result<Self, String> {
+    pub fn from_symbolic_bits(bits: &str) -> Result<Self, PermconError> {
         let chars: Vec<char> = bits.chars().collect();
 
         match chars[..] {
             [r, w, x] => {
+                let invalid_char = chars
+                    .iter()
+                    .zip(SYMBOLIC_BIT_CHARS)
+                    .enumerate()
+                    .find(|(_, (ch, (allowed, _)))| !allowed.contains(**ch));
+
+                if let Some((position, (found, (_, expected)))) = invalid_char {
+                    return Err(PermconError::InvalidChar {
+                        input: bits.to_string(),
+                        position,
+                        found: *found,
+                        expected: expected.to_string(),
+                    });
+                }
+
                 let (execute, special) = parse_symbolic_execution_bit(x);
 
-                return Ok(GroupPermission {
+                Ok(GroupPermission {
                     execute,
                     special,
                     read: r == 'r',
                     write: w == 'w',
-                });
+                })
             }
-            _ => return Err(String::from("Invalid permission bits length!")),
+            _ => Err(PermconError::InvalidLength {
+                input: bits.to_string(),
+                expected: vec![3],
+                found: chars.len(),
+            }),
         }
     }
 
@@ -243,8 +448,8 @@ impl GroupPermission {
     ///     }
     /// );
     /// ```
-    pub fn from_octal_digit(digit: u8, is_special: bool) -> Result<Self, String> {
-        let [read, write, execute] = parse_octal_digit(digit).unwrap();
+    pub fn from_octal_digit(digit: u8, is_special: bool) -> Result<Self, PermconError> {
+        let [read, write, execute] = parse_octal_digit(digit)?;
 
         Ok(GroupPermission {
             read,
@@ -288,7 +493,102 @@ impl GroupPermission {
     }
 }
 
-// ---------- Util to serialize FilePermission::special field -------------
+// ---------- The JSON layout of FilePermission -------------
+#[derive(Serialize, Deserialize)]
+struct FilePermissionJson {
+    user: GroupPermission,
+    group: GroupPermission,
+    other: GroupPermission,
+    filetype: String,
+    filetype_char: char,
+    #[serde(default)]
+    source_format: Option<SourceFormat>,
+    #[serde(
+        serialize_with = "serialize_special_permissions",
+        deserialize_with = "deserialize_special_permissions"
+    )]
+    special: [SpecialPermission; 3],
+    #[serde(default)]
+    octal: Option<String>,
+    #[serde(default)]
+    symbolic: Option<String>,
+}
+
+impl From<FilePermission> for FilePermissionJson {
+    fn from(perm: FilePermission) -> Self {
+        FilePermissionJson {
+            octal: Some(perm.to_octal_str()),
+            symbolic: Some(perm.to_symbolic_str()),
+            user: perm.user,
+            group: perm.group,
+            other: perm.other,
+            filetype: perm.filetype.to_string(),
+            filetype_char: perm.filetype.to_char(),
+            source_format: perm.source_format,
+            special: perm.special,
+        }
+    }
+}
+
+impl TryFrom<FilePermissionJson> for FilePermission {
+    type Error = String;
+
+    /// The `octal` and `symbolic` strings are optional but must agree with
+    /// the other fields if present. So must the `filetype` and its char.
+    fn try_from(json: FilePermissionJson) -> Result<Self, Self::Error> {
+        let filetype = FileType::from_str(&json.filetype).map_err(|error| error.to_string())?;
+
+        if filetype.to_char() != json.filetype_char {
+            return Err(format!(
+                "The filetype_char '{}' doesn't match the filetype \"{}\" ('{}')!",
+                json.filetype_char,
+                filetype,
+                filetype.to_char()
+            ));
+        }
+
+        let [user, group, other] = [json.user, json.group, json.other]
+            .into_iter()
+            .zip(&json.special)
+            .map(|(perm, special)| GroupPermission {
+                special: *special != Nil,
+                ..perm
+            })
+            .collect::<Vec<_>>()
+            .try_into()
+            .unwrap();
+
+        let perm = FilePermission {
+            user,
+            group,
+            other,
+            filetype,
+            source_format: json.source_format,
+            special: json.special,
+        };
+
+        for (field, value, expected) in [
+            ("octal", json.octal, perm.to_octal_str()),
+            ("symbolic", json.symbolic, perm.to_symbolic_str()),
+        ] {
+            if let Some(value) = value.filter(|value| *value != expected) {
+                return Err(format!(
+                    "The {field} permission \"{value}\" doesn't match the other fields (\"{expected}\")!"
+                ));
+            }
+        }
+
+        Ok(perm)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedSpecialPermissions {
+    suid: bool,
+    sgid: bool,
+    sticky_bit: bool,
+}
+
 fn serialize_special_permissions<S>(
     perms: &[SpecialPermission; 3],
     serializer: S,
@@ -296,14 +596,7 @@ fn serialize_special_permissions<S>(
 where
     S: Serializer,
 {
-    #[derive(Serialize)]
-    struct SerializedSpecialPermissions {
-        suid: bool,
-        sgid: bool,
-        sticky_bit: bool,
-    }
-
-    let is_set = |value: &SpecialPermission| value == &SpecialPermission::Nil;
+    let is_set = |value: &SpecialPermission| value != &SpecialPermission::Nil;
 
     let special_permissions = SerializedSpecialPermissions {
         suid: is_set(&perms[0]),
@@ -313,3 +606,190 @@ where
 
     special_permissions.serialize(serializer)
 }
+
+fn deserialize_special_permissions<'de, D>(
+    deserializer: D,
+) -> Result<[SpecialPermission; 3], D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let special = SerializedSpecialPermissions::deserialize(deserializer)?;
+
+    Ok(get_special_perms_array(
+        &[special.suid, special.sgid, special.sticky_bit],
+        |is_set| *is_set,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        for perm_str in ["0644", "1755", "-rwsr-x--x", "drwxrwsrwT", "prw-------"] {
+            let perm = FilePermission::try_from(perm_str).unwrap();
+
+            for pretty in [false, true] {
+                assert_eq!(
+                    FilePermission::from_json(&perm.to_json(pretty)).unwrap(),
+                    perm
+                );
+            }
+        }
+
+        // an octal permission has no file type, shown as `-`
+        let json: serde_json::Value =
+            serde_json::from_str(&FilePermission::try_from("0755").unwrap().to_json(false))
+                .unwrap();
+
+        assert_eq!(json["filetype"], "Unknown");
+        assert_eq!(json["filetype_char"], "-");
+    }
+
+    #[test]
+    fn test_parse_ls_attributes() {
+        for attribute in ['+', '.', '@'] {
+            assert_eq!(
+                FilePermission::try_from(format!("-rwxrwxr-x{attribute}").as_str()),
+                FilePermission::try_from("-rwxrwxr-x"),
+                "{attribute}"
+            );
+        }
+
+        // only after a mode with a file type, like `ls -l` prints it
+        for perm_str in ["rwxrwxr-x+", "-rwxrwxr-x?", "-rwxrwxr-x++", "0755+"] {
+            assert!(FilePermission::try_from(perm_str).is_err(), "{perm_str}");
+        }
+    }
+
+    #[test]
+    fn test_special_permission_flags() {
+        let perm = FilePermission::try_from("1755").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&perm.to_json(false)).unwrap();
+
+        assert_eq!(
+            json["special"],
+            json!({ "suid": false, "sgid": false, "sticky_bit": true })
+        );
+    }
+
+    #[test]
+    fn test_all_octal_modes_serialize_consistently() {
+        for bits in 0..=0o7777u16 {
+            let octal = format!("{bits:04o}");
+            let perm = FilePermission::try_from(octal.as_str()).unwrap();
+            let symbolic = perm.to_symbolic_str();
+
+            assert_eq!(perm.to_octal_str(), octal);
+
+            let reparsed = FilePermission::try_from(symbolic.as_str()).unwrap();
+            assert_eq!(reparsed.to_octal_str(), octal);
+            assert_eq!(reparsed.to_symbolic_str(), symbolic);
+
+            let json: serde_json::Value = serde_json::from_str(&perm.to_json(false)).unwrap();
+            assert_eq!(json["octal"], octal.as_str());
+            assert_eq!(json["symbolic"], symbolic.as_str());
+
+            let is_set = |bit: u16| bits & bit != 0;
+            assert_eq!(
+                json["special"],
+                json!({
+                    "suid": is_set(0o4000),
+                    "sgid": is_set(0o2000),
+                    "sticky_bit": is_set(0o1000),
+                }),
+                "{octal}"
+            );
+
+            for (class, shift) in [("user", 6), ("group", 3), ("other", 0)] {
+                assert_eq!(
+                    json[class],
+                    json!({
+                        "read": is_set(0o4 << shift),
+                        "write": is_set(0o2 << shift),
+                        "execute": is_set(0o1 << shift),
+                    }),
+                    "{octal} {class}"
+                );
+            }
+
+            assert_eq!(FilePermission::from_json(&json.to_string()).unwrap(), perm);
+        }
+    }
+
+    #[test]
+    fn test_chmod_str_sets_all_octal_modes() {
+        let test_cases: [(&str, &str); 5] = [
+            ("0000", "a="),
+            ("0700", "u=rwx,go="),
+            ("0604", "u=rw,g=,o=r"),
+            ("6711", "u=rwx,go=x,ug+s"),
+            ("5555", "a=rx,u+s,+t"),
+        ];
+
+        for (octal, expr) in test_cases {
+            let perm = FilePermission::try_from(octal).unwrap();
+            assert_eq!(perm.to_chmod_str(), expr);
+        }
+
+        let [empty, full] = ["0000", "7777"].map(|perm| FilePermission::try_from(perm).unwrap());
+
+        for bits in 0..=0o7777u16 {
+            let expr = FilePermission::from_bits(bits, FileType::Regular).to_chmod_str();
+
+            assert_eq!(empty.apply(&expr).unwrap().to_bits(), bits, "{expr}");
+            assert_eq!(full.apply(&expr).unwrap().to_bits(), bits, "{expr}");
+        }
+    }
+
+    #[test]
+    fn test_from_json() {
+        let json = r#"{
+            "user": { "read": true, "write": true, "execute": false },
+            "group": { "read": true, "write": false, "execute": true },
+            "other": { "read": false, "write": false, "execute": false },
+            "filetype": "Directory",
+            "filetype_char": "d",
+            "special": { "suid": false, "sgid": true, "sticky_bit": false }
+        }"#;
+
+        let perm = FilePermission::from_json(json).unwrap();
+
+        assert_eq!(perm.to_symbolic_str(), "drw-r-s---");
+        assert_eq!(perm.source_format, None);
+        assert!(perm.group.special);
+
+        let json = json.replace("\"filetype\"", "\"octal\": \"2650\", \"filetype\"");
+        assert!(FilePermission::from_json(&json).is_ok());
+
+        let json = json.replace("2650", "0650");
+        assert!(FilePermission::from_json(&json).is_err());
+
+        let json = json.replace("0650", "2650").replace("\"d\"", "\"-\"");
+        assert!(FilePermission::from_json(&json).is_err());
+
+        let json = json.replace("\"Directory\"", "\"Unknown\"");
+        assert_eq!(
+            FilePermission::from_json(&json).unwrap().filetype,
+            FileType::Unknown
+        );
+    }
+
+    #[test]
+    fn test_json_matches_schema_properties() {
+        let schema: serde_json::Value = serde_json::from_str(JSON_SCHEMA).unwrap();
+        let perm = FilePermission::try_from("-rwxr-xr-x").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&perm.to_json(false)).unwrap();
+
+        let mut keys: Vec<_> = json.as_object().unwrap().keys().collect();
+        let mut properties: Vec<_> = schema["properties"].as_object().unwrap().keys().collect();
+        keys.sort();
+        properties.sort();
+
+        assert_eq!(keys, properties);
+
+        let filetypes: Vec<_> = FileType::ALL.iter().map(FileType::to_string).collect();
+        assert_eq!(schema["properties"]["filetype"]["enum"], json!(filetypes));
+    }
+}
@@ -0,0 +1,455 @@
+use crate::{
+    error::PermconError,
+    filetype::FileType,
+    perm::{FilePermission, GroupPermission, SpecialPermission},
+};
+use serde_json::{json, Value};
+use std::{fmt, str::FromStr};
+
+/// The notations a permission can be rendered in, see
+/// [`FilePermission::render`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum Format {
+    /// `0755`
+    Octal,
+    /// `755`, or 4 digits if a special bit is set (`4755`).
+    ShortOctal,
+    /// `-rwxr-xr-x`
+    Symbolic,
+    /// `rwxr-xr-x`, without the file type char.
+    SymbolicBits,
+    /// An absolute chmod expression, `u=rwx,go=rx`.
+    Chmod,
+    /// The full `st_mode` with the file type bits, `100755`.
+    Mode,
+    /// An English sentence, e.g., `The owner can read and write; ...`.
+    Description,
+    /// The compact JSON of [`FilePermission::to_json`].
+    Json,
+    /// The JSON document as YAML.
+    Yaml,
+    /// A table of the classes and their bits.
+    Table,
+}
+
+impl Format {
+    /// All the formats.
+    pub const ALL: [Format; 10] = [
+        Format::Octal,
+        Format::ShortOctal,
+        Format::Symbolic,
+        Format::SymbolicBits,
+        Format::Chmod,
+        Format::Mode,
+        Format::Description,
+        Format::Json,
+        Format::Yaml,
+        Format::Table,
+    ];
+
+    /// Returns the name of the format (e.g., `short-octal`).
+    pub const fn name(self) -> &'static str {
+        match self {
+            Format::Octal => "octal",
+            Format::ShortOctal => "short-octal",
+            Format::Symbolic => "symbolic",
+            Format::SymbolicBits => "symbolic-bits",
+            Format::Chmod => "chmod",
+            Format::Mode => "mode",
+            Format::Description => "description",
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Table => "table",
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    /// Formats the name, see [`Format::name`].
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Format {
+    type Err = PermconError;
+
+    /// Tries to parse the name of a format (e.g., `symbolic`).
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        Format::ALL
+            .into_iter()
+            .find(|format| format.name() == name)
+            .ok_or_else(|| PermconError::InvalidValue {
+                input: name.to_string(),
+                expected: format!(
+                    "one of {}",
+                    Format::ALL.map(|format| format!("'{format}'")).join(", ")
+                ),
+            })
+    }
+}
+
+impl FilePermission {
+    /// Renders the permission in the given notation.
+    ///
+    /// ```rust
+    /// use permcon::{render::Format, FilePermission};
+    ///
+    /// let perm = FilePermission::try_from("drwxr-x---").unwrap();
+    ///
+    /// assert_eq!(perm.render(Format::ShortOctal), "750");
+    /// assert_eq!(perm.render(Format::SymbolicBits), "rwxr-x---");
+    /// assert_eq!(perm.render(Format::Mode), "040750");
+    /// assert_eq!(
+    ///     perm.render(Format::Description),
+    ///     "The owner can read, write and execute; the group can read and execute; others can't do anything."
+    /// );
+    /// ```
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Octal => self.to_octal_str(),
+            Format::ShortOctal => {
+                let octal = self.to_octal_str();
+
+                match octal.strip_prefix('0') {
+                    Some(short) => short.to_string(),
+                    None => octal,
+                }
+            }
+            Format::Symbolic => self.to_symbolic_str(),
+            Format::SymbolicBits => self.to_symbolic_bits_arr().join(""),
+            Format::Chmod => self.to_chmod_str(),
+            Format::Mode => self.to_full_octal_str(),
+            Format::Description => to_description(self),
+            Format::Json => self.to_json(false),
+            Format::Yaml => to_yaml(&json!(self), 0),
+            Format::Table => to_table(self),
+        }
+    }
+}
+
+/// A field of a [`Template`].
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Field {
+    Format(Format),
+    /// The name of the file type (e.g., `Directory`).
+    FileType,
+    /// The `ls -l` file type char, `?` like `ls` if it's unknown.
+    FileTypeChar,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Part {
+    Text(String),
+    Field(Field),
+}
+
+/// A template with `{name}` fields (e.g., `{octal} {symbolic} {filetype}`)
+/// to render permissions with. A field is the name of a [`Format`],
+/// `filetype` or `filetype-char`; `{{` and `}}` stand for literal braces.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::{render::Template, FilePermission};
+///
+/// let template = Template::from_str("{octal} {symbolic} {filetype} {{{chmod}}}").unwrap();
+/// let perm = FilePermission::try_from("drwxr-xr-x").unwrap();
+///
+/// assert_eq!(template.render(&perm), "0755 drwxr-xr-x Directory {u=rwx,go=rx}");
+/// assert!(Template::from_str("{octal").is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    /// Renders `permission` with the template.
+    pub fn render(&self, permission: &FilePermission) -> String {
+        self.parts
+            .iter()
+            .map(|part| match part {
+                Part::Text(text) => text.clone(),
+                Part::Field(Field::Format(format)) => permission.render(*format),
+                Part::Field(Field::FileType) => permission.filetype.to_string(),
+                Part::Field(Field::FileTypeChar) => permission.filetype.to_char().to_string(),
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Template {
+    type Err = PermconError;
+
+    /// Tries to parse a template, see [`Template`].
+    fn from_str(template: &str) -> Result<Self, Self::Err> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().enumerate().peekable();
+
+        while let Some((position, ch)) = chars.next() {
+            match ch {
+                '{' | '}' if chars.peek().map(|(_, next)| *next) == Some(ch) => {
+                    chars.next();
+                    text.push(ch);
+                }
+                '{' => {
+                    let mut name = String::new();
+                    let mut is_closed = false;
+
+                    for (_, ch) in chars.by_ref() {
+                        if ch == '}' {
+                            is_closed = true;
+                            break;
+                        }
+
+                        name.push(ch);
+                    }
+
+                    if !is_closed {
+                        return Err(PermconError::UnexpectedEnd {
+                            input: template.to_string(),
+                            expected: String::from("'}'"),
+                        });
+                    }
+
+                    let field = match name.as_str() {
+                        "filetype" => Field::FileType,
+                        "filetype-char" => Field::FileTypeChar,
+                        name => Field::Format(Format::from_str(name).map_err(|_| {
+                            PermconError::InvalidValue {
+                                input: name.to_string(),
+                                expected: String::from(
+                                    "the name of a format, 'filetype' or 'filetype-char'",
+                                ),
+                            }
+                        })?),
+                    };
+
+                    parts.push(Part::Text(std::mem::take(&mut text)));
+                    parts.push(Part::Field(field));
+                }
+                '}' => {
+                    return Err(PermconError::InvalidChar {
+                        input: template.to_string(),
+                        position,
+                        found: '}',
+                        expected: String::from("'}}' for a literal '}'"),
+                    })
+                }
+                _ => text.push(ch),
+            }
+        }
+
+        parts.push(Part::Text(text));
+        parts.retain(|part| *part != Part::Text(String::new()));
+
+        Ok(Template { parts })
+    }
+}
+
+/// Returns e.g. `read, write and execute`, or `None` without any bit.
+fn to_rights_str(perm: &GroupPermission) -> Option<String> {
+    let mut rights: Vec<&str> = ["read", "write", "execute"]
+        .into_iter()
+        .zip(perm.as_rwx_array())
+        .filter(|(_, is_set)| *is_set)
+        .map(|(right, _)| right)
+        .collect();
+
+    let last = rights.pop()?;
+
+    if rights.is_empty() {
+        Some(last.to_string())
+    } else {
+        Some(format!("{} and {last}", rights.join(", ")))
+    }
+}
+
+fn to_description(permission: &FilePermission) -> String {
+    let classes = ["The owner", "the group", "others"]
+        .into_iter()
+        .zip(permission.to_perm_group_array())
+        .map(|(class, perm)| match to_rights_str(perm) {
+            Some(rights) => format!("{class} can {rights}"),
+            None => format!("{class} can't do anything"),
+        })
+        .collect::<Vec<_>>()
+        .join("; ");
+
+    let mut sentences = vec![format!("{classes}.")];
+
+    let (is_directory, may_be_directory) = match permission.filetype {
+        FileType::Directory => (true, true),
+        FileType::Unknown => (false, true),
+        _ => (false, false),
+    };
+
+    for special in &permission.special {
+        let sentence = match special {
+            SpecialPermission::Nil => continue,
+            SpecialPermission::SUID => "It runs with the privileges of its owner (setuid).",
+            SpecialPermission::SGID if is_directory => {
+                "New entries inherit its group (setgid)."
+            }
+            SpecialPermission::SGID if may_be_directory => {
+                "It runs with the privileges of its group, or new entries inherit its group if it's a directory (setgid)."
+            }
+            SpecialPermission::SGID => "It runs with the privileges of its group (setgid).",
+            SpecialPermission::StickyBit if is_directory => {
+                "Only the owner of an entry can delete or rename it (sticky)."
+            }
+            SpecialPermission::StickyBit if may_be_directory => {
+                "Only the owner of an entry can delete or rename it if it's a directory (sticky)."
+            }
+            SpecialPermission::StickyBit => "The sticky bit has no effect on it (sticky).",
+        };
+
+        sentences.push(sentence.to_string());
+    }
+
+    sentences.join(" ")
+}
+
+/// Renders a JSON value as block style YAML. Strings stay double-quoted,
+/// which YAML reads the same way as JSON.
+fn to_yaml(value: &Value, indent: usize) -> String {
+    let padding = " ".repeat(indent);
+
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| match value {
+                Value::Object(nested) if !nested.is_empty() => {
+                    format!("{padding}{key}:\n{}", to_yaml(value, indent + 2))
+                }
+                _ => format!("{padding}{key}: {}", to_yaml(value, 0)),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        value => value.to_string(),
+    }
+}
+
+fn to_table(permission: &FilePermission) -> String {
+    let mut rows = vec![String::from("class  read  write  execute  special")];
+
+    for ((class, perm), special) in ["user", "group", "other"]
+        .into_iter()
+        .zip(permission.to_perm_group_array())
+        .zip(["setuid", "setgid", "sticky"])
+    {
+        let bit = |is_set: bool, name| if is_set { name } else { "no" };
+        let (read, write, execute) = (
+            bit(perm.read, "yes"),
+            bit(perm.write, "yes"),
+            bit(perm.execute, "yes"),
+        );
+        let special = bit(perm.special, special);
+
+        rows.push(format!(
+            "{class:<6} {read:<5} {write:<6} {execute:<8} {special}"
+        ));
+    }
+
+    rows.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_formats() {
+        let perm = FilePermission::try_from("-rwsr-x--T").unwrap();
+
+        let test_cases: [(Format, &str); 8] = [
+            (Format::Octal, "5750"),
+            (Format::ShortOctal, "5750"),
+            (Format::Symbolic, "-rwsr-x--T"),
+            (Format::SymbolicBits, "rwsr-x--T"),
+            (Format::Chmod, "u=rwx,g=rx,o=,u+s,+t"),
+            (Format::Mode, "105750"),
+            (
+                Format::Description,
+                "The owner can read, write and execute; the group can read and execute; others can't do anything. It runs with the privileges of its owner (setuid). The sticky bit has no effect on it (sticky).",
+            ),
+            (
+                Format::Table,
+                "class  read  write  execute  special\n\
+                 user   yes   yes    yes      setuid\n\
+                 group  yes   no     yes      no\n\
+                 other  no    no     no       sticky",
+            ),
+        ];
+
+        for (format, output) in test_cases {
+            assert_eq!(perm.render(format), output, "{format}");
+            assert_eq!(Format::from_str(format.name()), Ok(format));
+        }
+
+        assert_eq!(
+            FilePermission::from_json(&perm.render(Format::Json)).unwrap(),
+            perm
+        );
+        assert!(Format::from_str("xml").is_err());
+    }
+
+    #[test]
+    fn test_render_yaml() {
+        let perm = FilePermission::try_from("0640").unwrap();
+        let yaml = perm.render(Format::Yaml);
+
+        assert!(yaml.starts_with(
+            "filetype: \"Unknown\"\nfiletype_char: \"-\"\ngroup:\n  execute: false\n"
+        ));
+        assert!(yaml.contains("\noctal: \"0640\"\n"));
+        assert!(yaml.ends_with("user:\n  execute: false\n  read: true\n  write: true"));
+    }
+
+    #[test]
+    fn test_templates() {
+        let perm = FilePermission::try_from("0644").unwrap();
+
+        let test_cases: [(&str, &str); 4] = [
+            ("{octal} {symbolic} {filetype}", "0644 -rw-r--r-- Unknown"),
+            ("{short-octal}", "644"),
+            ("mode={{{mode}}} {filetype-char}", "mode={0644} -"),
+            ("plain text", "plain text"),
+        ];
+
+        for (template, output) in test_cases {
+            assert_eq!(Template::from_str(template).unwrap().render(&perm), output);
+        }
+
+        let errors = [
+            (
+                "{octal",
+                PermconError::UnexpectedEnd {
+                    input: String::from("{octal"),
+                    expected: String::from("'}'"),
+                },
+            ),
+            (
+                "{octal} }",
+                PermconError::InvalidChar {
+                    input: String::from("{octal} }"),
+                    position: 8,
+                    found: '}',
+                    expected: String::from("'}}' for a literal '}'"),
+                },
+            ),
+            (
+                "{owner}",
+                PermconError::InvalidValue {
+                    input: String::from("owner"),
+                    expected: String::from("the name of a format, 'filetype' or 'filetype-char'"),
+                },
+            ),
+        ];
+
+        for (template, error) in errors {
+            assert_eq!(Template::from_str(template), Err(error), "{template}");
+        }
+    }
+}
@@ -1,15 +1,20 @@
+use crate::error::PermconError;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::str::FromStr;
 
 const SYMBOLIC_PATTERN_GROUPS: [&str; 4] = ["filetype", "user", "group", "other"];
+const FILETYPE_CHARS: &str = "bcdlpsDw-";
+
+/// The allowed chars of every position of the permission bits (`rwxrwxrwx`).
+const BIT_CHARS: [&str; 9] = ["r-", "w-", "xsS-", "r-", "w-", "xsS-", "r-", "w-", "xtT-"];
 
 lazy_static! {
     /// A regex pattern to parse symbolic (e.g., `drwxr-xr-x`) permission string.
     ///
-    /// `pattern = r"(?x)^ (?P<filetype>[bcdlps-])? (?P<user>[r-][w-][xsS-]) (?P<group>[r-][w-][xsS-]) (?P<other>[r-][w-][xtT-])$"`
+    /// `pattern = r"(?x)^ (?P<filetype>[bcdlpsDw-])? (?P<user>[r-][w-][xsS-]) (?P<group>[r-][w-][xsS-]) (?P<other>[r-][w-][xtT-])$"`
     pub static ref SYMBOLIC_PATTERN: Regex = Regex::new(
-        r"(?x)^ (?P<filetype>[bcdlps-])? (?P<user>[r-][w-][xsS-]) (?P<group>[r-][w-][xsS-]) (?P<other>[r-][w-][xtT-])$"
+        r"(?x)^ (?P<filetype>[bcdlpsDw-])? (?P<user>[r-][w-][xsS-]) (?P<group>[r-][w-][xsS-]) (?P<other>[r-][w-][xtT-])$"
     )
     .unwrap();
 }
@@ -44,17 +49,17 @@ impl Symbolic {
     /// Checks whether the given permission string is a valid permission in the
     /// symbolic notation.
     pub fn is_valid(permission: &str) -> bool {
-        return SYMBOLIC_PATTERN.is_match(permission);
+        SYMBOLIC_PATTERN.is_match(permission)
     }
 }
 
 impl FromStr for Symbolic {
-    type Err = String;
+    type Err = PermconError;
 
     /// Tries to parse the permission string with symbolic format
     fn from_str(permission: &str) -> Result<Self, Self::Err> {
         if !Self::is_valid(permission) {
-            return Err(String::from("Invalid symbolic permission."));
+            return Err(find_error(permission));
         }
 
         let caps = SYMBOLIC_PATTERN
@@ -63,7 +68,7 @@ impl FromStr for Symbolic {
 
         let values = SYMBOLIC_PATTERN_GROUPS
             .map(|group_name| caps.name(group_name).map_or("-", |val| val.as_str()))
-            .map(|val| String::from(val));
+            .map(String::from);
 
         let [filetype, user, group, other] = values;
 
@@ -75,3 +80,95 @@ impl FromStr for Symbolic {
         })
     }
 }
+
+/// Finds out why `permission` doesn't match the `SYMBOLIC_PATTERN`.
+fn find_error(permission: &str) -> PermconError {
+    let chars: Vec<char> = permission.chars().collect();
+
+    if chars.len() != 9 && chars.len() != 10 {
+        return PermconError::InvalidLength {
+            input: permission.to_string(),
+            expected: vec![9, 10],
+            found: chars.len(),
+        };
+    }
+
+    let offset = chars.len() - 9;
+
+    if offset == 1 && !FILETYPE_CHARS.contains(chars[0]) {
+        return PermconError::UnknownFileType {
+            input: permission.to_string(),
+            position: 0,
+            found: chars[0],
+        };
+    }
+
+    chars[offset..]
+        .iter()
+        .zip(BIT_CHARS)
+        .enumerate()
+        .find(|(_, (ch, allowed))| !allowed.contains(**ch))
+        .map(|(index, (ch, allowed))| PermconError::InvalidChar {
+            input: permission.to_string(),
+            position: index + offset,
+            found: *ch,
+            expected: format!(
+                "one of {}",
+                allowed
+                    .chars()
+                    .map(|ch| format!("'{ch}'"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        })
+        .expect("The permission must have an invalid char as it didn't match the pattern.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_errors() {
+        let test_cases = [
+            (
+                "rwxr-x",
+                PermconError::InvalidLength {
+                    input: String::from("rwxr-x"),
+                    expected: vec![9, 10],
+                    found: 6,
+                },
+            ),
+            (
+                "xrwxr-xr-x",
+                PermconError::UnknownFileType {
+                    input: String::from("xrwxr-xr-x"),
+                    position: 0,
+                    found: 'x',
+                },
+            ),
+            (
+                "drwxr-tr-x",
+                PermconError::InvalidChar {
+                    input: String::from("drwxr-tr-x"),
+                    position: 6,
+                    found: 't',
+                    expected: String::from("one of 'x', 's', 'S', '-'"),
+                },
+            ),
+            (
+                "rw-rw-rw?",
+                PermconError::InvalidChar {
+                    input: String::from("rw-rw-rw?"),
+                    position: 8,
+                    found: '?',
+                    expected: String::from("one of 'x', 't', 'T', '-'"),
+                },
+            ),
+        ];
+
+        for (permission, error) in test_cases {
+            assert_eq!(Symbolic::from_str(permission), Err(error), "{permission}");
+        }
+    }
+}
@@ -0,0 +1,532 @@
+use crate::{
+    filetype::FileType,
+    fs::{ModeSpec, PermissionChange},
+    perm::FilePermission,
+};
+use std::{
+    io::{self, Read, Write},
+    path::PathBuf,
+};
+
+/// The size of a tar header and of the blocks the entry data is padded to.
+pub const BLOCK_SIZE: usize = 512;
+
+/// The byte ranges of the header fields permcon reads or writes.
+const NAME: (usize, usize) = (0, 100);
+const MODE: (usize, usize) = (100, 108);
+const UID: (usize, usize) = (108, 116);
+const GID: (usize, usize) = (116, 124);
+const SIZE: (usize, usize) = (124, 136);
+const CHECKSUM: (usize, usize) = (148, 156);
+const TYPEFLAG: usize = 156;
+const LINKNAME: (usize, usize) = (157, 257);
+const MAGIC: (usize, usize) = (257, 263);
+const UNAME: (usize, usize) = (265, 297);
+const GNAME: (usize, usize) = (297, 329);
+const PREFIX: (usize, usize) = (345, 500);
+
+/// Represents an entry of a tar archive.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TarEntry {
+    pub path: String,
+    /// The permission of the mode field with the file type of the entry type.
+    /// A hard link is a regular file.
+    pub permission: FilePermission,
+    /// The user name, or the uid if the archive has none.
+    pub owner: String,
+    /// The group name, or the gid if the archive has none.
+    pub group: String,
+    pub size: u64,
+    /// The target of a symbolic or hard link.
+    pub link_target: Option<String>,
+}
+
+/// Options of [`normalize_tar`]. A `None` mode leaves the matching entries
+/// untouched.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct TarNormalizeOptions {
+    /// The mode applied to regular files (and hard links).
+    pub file_mode: Option<ModeSpec>,
+    /// The mode applied to directories.
+    pub directory_mode: Option<ModeSpec>,
+}
+
+/// Reads the entries of a ustar, pax or GNU tar archive. Long names of pax
+/// and GNU archives are resolved, the archive must not be compressed.
+///
+/// ```rust
+/// use permcon::tar::read_tar_entries;
+/// use permcon::FileType;
+///
+/// let archive = std::fs::File::open("tests/fixtures/release-pax.tar").unwrap();
+/// let entries = read_tar_entries(archive).unwrap();
+///
+/// assert_eq!(entries[0].path, "release/");
+/// assert_eq!(entries[0].permission.filetype, FileType::Directory);
+/// assert_eq!(entries[2].permission.to_symbolic_str(), "-rwsr-xr-x");
+/// assert_eq!(entries[3].link_target.as_deref(), Some("bin/run"));
+/// ```
+pub fn read_tar_entries<R: Read>(reader: R) -> io::Result<Vec<TarEntry>> {
+    let mut entries = Vec::new();
+
+    walk_tar(reader, |_, entry| {
+        if let Some(entry) = entry {
+            entries.push(entry.clone());
+        }
+
+        Ok(())
+    })?;
+
+    Ok(entries)
+}
+
+/// Copies the archive of `reader` to `writer` with the mode of every
+/// directory and regular file rewritten by `options`. Everything else,
+/// including the file type and the content, is copied byte for byte.
+///
+/// Returns the change of every rewritten entry, in archive order.
+///
+/// ```rust,no_run
+/// use std::{fs::File, str::FromStr};
+/// use permcon::fs::ModeSpec;
+/// use permcon::tar::{normalize_tar, TarNormalizeOptions};
+///
+/// // dirs 0755, files 0644, but files that are already executable keep it
+/// let options = TarNormalizeOptions {
+///     directory_mode: Some(ModeSpec::from_str("0755").unwrap()),
+///     file_mode: Some(ModeSpec::from_str("u=rwX,go=rX").unwrap()),
+/// };
+///
+/// let archive = File::open("release.tar").unwrap();
+/// let normalized = File::create("release.normalized.tar").unwrap();
+///
+/// for change in normalize_tar(archive, normalized, &options).unwrap() {
+///     println!("{}: {}", change.path.display(), change.after.to_octal_str());
+/// }
+/// ```
+pub fn normalize_tar<R: Read, W: Write>(
+    reader: R,
+    mut writer: W,
+    options: &TarNormalizeOptions,
+) -> io::Result<Vec<PermissionChange>> {
+    let mut changes = Vec::new();
+
+    walk_tar(reader, |block, entry| {
+        let mode = entry.and_then(|entry| match entry.permission.filetype {
+            FileType::Directory => options.directory_mode.as_ref(),
+            FileType::Regular => options.file_mode.as_ref(),
+            _ => None,
+        });
+
+        if let (Some(entry), Some(mode)) = (entry, mode) {
+            let after = mode.resolve(&entry.permission);
+
+            // keep the file type bits some archivers write into the field
+            let mode_bits = parse_number(&block[MODE.0..MODE.1]).unwrap_or(0) & !0o7777;
+            write_octal(
+                &mut block[MODE.0..MODE.1],
+                mode_bits | u64::from(after.to_bits()),
+            );
+            write_checksum(block);
+
+            changes.push(PermissionChange {
+                path: PathBuf::from(&entry.path),
+                before: entry.permission.clone(),
+                after,
+            });
+        }
+
+        writer.write_all(block)
+    })?;
+
+    writer.flush()?;
+
+    Ok(changes)
+}
+
+/// The path, link target and size a pax or GNU header sets for the next
+/// entry.
+#[derive(Default)]
+struct PendingNames {
+    path: Option<String>,
+    link_target: Option<String>,
+    size: Option<u64>,
+}
+
+/// Reads `reader` block by block and calls `visit` with every block, in
+/// order, along with the entry if the block is the header of one.
+fn walk_tar<R: Read>(
+    mut reader: R,
+    mut visit: impl FnMut(&mut [u8; BLOCK_SIZE], Option<&TarEntry>) -> io::Result<()>,
+) -> io::Result<()> {
+    let mut block = [0u8; BLOCK_SIZE];
+    let mut pending = PendingNames::default();
+    let mut offset = 0u64;
+    let mut is_ended = false;
+
+    while read_block(&mut reader, &mut block, offset)? {
+        // everything after the end-of-archive block is copied as it is
+        if is_ended || block.iter().all(|byte| *byte == 0) {
+            is_ended = true;
+            visit(&mut block, None)?;
+            offset += BLOCK_SIZE as u64;
+            continue;
+        }
+
+        verify_checksum(&block, offset)?;
+
+        let typeflag = block[TYPEFLAG];
+        let size = match typeflag {
+            b'x' | b'g' | b'L' | b'K' => header_number(&block, SIZE, offset)?,
+            _ => pending
+                .size
+                .take()
+                .map_or_else(|| header_number(&block, SIZE, offset), Ok)?,
+        };
+
+        let data_blocks = match typeflag {
+            b'1' | b'2' | b'3' | b'4' | b'6' => 0,
+            _ => size.div_ceil(BLOCK_SIZE as u64),
+        };
+
+        let entry = match typeflag {
+            // extended headers, volume labels and multi-volume continuations
+            b'x' | b'g' | b'L' | b'K' | b'V' | b'M' => None,
+            _ => Some(parse_entry(&block, typeflag, size, &mut pending, offset)?),
+        };
+
+        visit(&mut block, entry.as_ref())?;
+        offset += BLOCK_SIZE as u64;
+
+        let mut data = Vec::new();
+
+        for _ in 0..data_blocks {
+            if !read_block(&mut reader, &mut block, offset)? {
+                return Err(invalid_data(offset, "the archive ends inside an entry"));
+            }
+
+            if matches!(typeflag, b'x' | b'L' | b'K') {
+                data.extend_from_slice(&block);
+            }
+
+            visit(&mut block, None)?;
+            offset += BLOCK_SIZE as u64;
+        }
+
+        data.truncate(size as usize);
+
+        match typeflag {
+            b'x' => apply_pax_records(&data, &mut pending),
+            b'L' => pending.path = Some(c_string(&data)),
+            b'K' => pending.link_target = Some(c_string(&data)),
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_entry(
+    header: &[u8; BLOCK_SIZE],
+    typeflag: u8,
+    size: u64,
+    pending: &mut PendingNames,
+    offset: u64,
+) -> io::Result<TarEntry> {
+    let name = c_string(&header[NAME.0..NAME.1]);
+    let prefix = c_string(&header[PREFIX.0..PREFIX.1]);
+
+    // GNU archives use the prefix field for other data
+    let is_ustar = &header[MAGIC.0..MAGIC.1] == b"ustar\0";
+
+    let path = match pending.path.take() {
+        Some(path) => path,
+        None if is_ustar && !prefix.is_empty() => format!("{prefix}/{name}"),
+        None => name,
+    };
+
+    let filetype = match typeflag {
+        b'0' | 0 if path.ends_with('/') => FileType::Directory,
+        b'2' => FileType::Symlink,
+        b'3' => FileType::CharDevice,
+        b'4' => FileType::BlockDevice,
+        b'5' | b'D' => FileType::Directory,
+        b'6' => FileType::Fifo,
+        // `0`, `1` (hard link), `7` (contiguous), `S` (GNU sparse) and any
+        // vendor extension are read as regular files
+        _ => FileType::Regular,
+    };
+
+    let link_target = match typeflag {
+        b'1' | b'2' => Some(
+            pending
+                .link_target
+                .take()
+                .unwrap_or_else(|| c_string(&header[LINKNAME.0..LINKNAME.1])),
+        ),
+        _ => None,
+    };
+
+    let mode = header_number(header, MODE, offset)?;
+    let owner_name = |field: (usize, usize), id: (usize, usize)| {
+        let name = c_string(&header[field.0..field.1]);
+
+        if name.is_empty() {
+            header_number(header, id, offset).map(|id| id.to_string())
+        } else {
+            Ok(name)
+        }
+    };
+
+    Ok(TarEntry {
+        path,
+        permission: FilePermission::from_bits((mode & 0o7777) as u16, filetype),
+        owner: owner_name(UNAME, UID)?,
+        group: owner_name(GNAME, GID)?,
+        size,
+        link_target,
+    })
+}
+
+/// Applies the `path`, `linkpath` and `size` records of a pax extended
+/// header (`<length> <key>=<value>\n`).
+fn apply_pax_records(data: &[u8], pending: &mut PendingNames) {
+    let mut rest = data;
+
+    while let Some(space) = rest.iter().position(|byte| *byte == b' ') {
+        let length = std::str::from_utf8(&rest[..space])
+            .ok()
+            .and_then(|length| length.parse::<usize>().ok());
+
+        let Some(length) = length.filter(|length| *length > space && *length <= rest.len()) else {
+            return;
+        };
+
+        let record = String::from_utf8_lossy(&rest[space + 1..length]);
+
+        if let Some((key, value)) = record.trim_end_matches('\n').split_once('=') {
+            match key {
+                "path" => pending.path = Some(value.to_string()),
+                "linkpath" => pending.link_target = Some(value.to_string()),
+                "size" => pending.size = value.parse().ok(),
+                _ => {}
+            }
+        }
+
+        rest = &rest[length..];
+    }
+}
+
+/// Fills `block` with the block at `offset` and returns `false` at the end
+/// of `reader`.
+fn read_block<R: Read>(
+    reader: &mut R,
+    block: &mut [u8; BLOCK_SIZE],
+    offset: u64,
+) -> io::Result<bool> {
+    let mut filled = 0;
+
+    while filled < BLOCK_SIZE {
+        match reader.read(&mut block[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    if offset == 0 && filled >= 2 && block[..2] == [0x1f, 0x8b] {
+        return Err(invalid_data(
+            offset,
+            "the archive is gzip compressed, decompress it first",
+        ));
+    }
+
+    match filled {
+        0 => Ok(false),
+        BLOCK_SIZE => Ok(true),
+        _ => Err(invalid_data(
+            offset,
+            "the archive ends inside a block of 512 bytes",
+        )),
+    }
+}
+
+fn verify_checksum(header: &[u8; BLOCK_SIZE], offset: u64) -> io::Result<()> {
+    let expected = header_number(header, CHECKSUM, offset)?;
+
+    // some old archivers summed the bytes as signed chars
+    let (unsigned, signed) = header
+        .iter()
+        .enumerate()
+        .fold((0u64, 0i64), |sums, (index, byte)| {
+            let byte = if (CHECKSUM.0..CHECKSUM.1).contains(&index) {
+                b' '
+            } else {
+                *byte
+            };
+
+            (sums.0 + u64::from(byte), sums.1 + i64::from(byte as i8))
+        });
+
+    if expected == unsigned || expected as i64 == signed {
+        Ok(())
+    } else {
+        Err(invalid_data(offset, "the header checksum doesn't match"))
+    }
+}
+
+fn write_checksum(header: &mut [u8; BLOCK_SIZE]) {
+    header[CHECKSUM.0..CHECKSUM.1].fill(b' ');
+
+    let sum: u64 = header.iter().map(|byte| u64::from(*byte)).sum();
+    header[CHECKSUM.0..CHECKSUM.1].copy_from_slice(format!("{sum:06o}\0 ").as_bytes());
+}
+
+/// Writes `value` as zero padded octal digits followed by a NUL.
+fn write_octal(field: &mut [u8], value: u64) {
+    let digits = format!("{value:0width$o}\0", width = field.len() - 1);
+    field.copy_from_slice(digits.as_bytes());
+}
+
+fn header_number(header: &[u8; BLOCK_SIZE], field: (usize, usize), offset: u64) -> io::Result<u64> {
+    parse_number(&header[field.0..field.1])
+        .ok_or_else(|| invalid_data(offset, "a numeric header field is malformed"))
+}
+
+/// Parses a numeric field: octal digits padded with spaces or NULs, or the
+/// big-endian base-256 encoding of GNU tar (first byte `0x80`).
+fn parse_number(field: &[u8]) -> Option<u64> {
+    if field.first().is_some_and(|byte| byte & 0x80 != 0) {
+        return field[1..]
+            .iter()
+            .try_fold(u64::from(field[0] & 0x7f), |value, byte| {
+                value.checked_mul(256).map(|value| value + u64::from(*byte))
+            });
+    }
+
+    let digits = String::from_utf8_lossy(field);
+    let digits = digits.trim_matches(|ch: char| ch == ' ' || ch == '\0');
+
+    if digits.is_empty() {
+        return Some(0);
+    }
+
+    u64::from_str_radix(digits, 8).ok()
+}
+
+/// Returns the text up to the first NUL.
+fn c_string(bytes: &[u8]) -> String {
+    let end = bytes
+        .iter()
+        .position(|byte| *byte == 0)
+        .unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..end]).into_owned()
+}
+
+fn invalid_data(offset: u64, message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid tar archive at byte {offset}: {message}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    const LONG_DIRECTORY: &str =
+        "release/share/a-rather-long-directory-name-to-push-the-path-over-one-hundred-chars/";
+
+    const FIXTURES: [&[u8]; 3] = [
+        include_bytes!("../tests/fixtures/release-gnu.tar"),
+        include_bytes!("../tests/fixtures/release-pax.tar"),
+        include_bytes!("../tests/fixtures/release-ustar.tar"),
+    ];
+
+    #[test]
+    fn test_read_fixtures() {
+        // (path, symbolic, link target)
+        let expected: [(String, &str, Option<&str>); 9] = [
+            (String::from("release/"), "drwxr-xr-x", None),
+            (String::from("release/bin/"), "drwxr-xr-x", None),
+            (String::from("release/bin/run"), "-rwsr-xr-x", None),
+            (String::from("release/run"), "lrwxrwxrwx", Some("bin/run")),
+            (String::from("release/share/"), "drwxr-xr-x", None),
+            (LONG_DIRECTORY.to_string(), "drwx------", None),
+            (
+                format!("{LONG_DIRECTORY}and-some-more/"),
+                "drwxr-xr-x",
+                None,
+            ),
+            (
+                format!("{LONG_DIRECTORY}and-some-more/data.json"),
+                "-rw-r-----",
+                None,
+            ),
+            (String::from("release/share/notes.txt"), "-rw-rw-rw-", None),
+        ];
+
+        for fixture in FIXTURES {
+            let entries = read_tar_entries(fixture).unwrap();
+
+            assert_eq!(entries.len(), expected.len());
+
+            for (entry, (path, symbolic, link_target)) in entries.iter().zip(&expected) {
+                assert_eq!(&entry.path, path);
+                assert_eq!(entry.permission.to_symbolic_str(), *symbolic, "{path}");
+                assert_eq!(entry.link_target.as_deref(), *link_target, "{path}");
+                assert_eq!(
+                    (entry.owner.as_str(), entry.group.as_str()),
+                    ("root", "root")
+                );
+            }
+
+            assert_eq!(entries[2].size, 10);
+        }
+    }
+
+    #[test]
+    fn test_normalize_fixtures() {
+        let options = TarNormalizeOptions {
+            directory_mode: Some(ModeSpec::from_str("0755").unwrap()),
+            file_mode: Some(ModeSpec::from_str("u=rwX,go=rX").unwrap()),
+        };
+
+        for fixture in FIXTURES {
+            let mut normalized = Vec::new();
+            let changes = normalize_tar(fixture, &mut normalized, &options).unwrap();
+
+            assert_eq!(normalized.len(), fixture.len());
+            assert_eq!(changes.len(), 8);
+
+            let entries = read_tar_entries(normalized.as_slice()).unwrap();
+            let modes: Vec<String> = entries
+                .iter()
+                .map(|entry| entry.permission.to_octal_str())
+                .collect();
+
+            // the setuid bit is cleared and the symbolic link is left as it is
+            assert_eq!(
+                modes,
+                ["0755", "0755", "0755", "0777", "0755", "0755", "0755", "0644", "0644"]
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_archives() {
+        let mut corrupted = FIXTURES[0].to_vec();
+        corrupted[MODE.0] = b'7';
+
+        let truncated = &FIXTURES[0][..BLOCK_SIZE + 100];
+        let gzip = [0x1f, 0x8b, 0x08, 0x00];
+
+        for archive in [&corrupted[..], truncated, &gzip[..]] {
+            assert!(read_tar_entries(archive).is_err());
+        }
+
+        assert_eq!(read_tar_entries(&[][..]).unwrap(), Vec::new());
+    }
+}
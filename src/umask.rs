@@ -0,0 +1,237 @@
+use crate::{chmod::ChmodExpr, error::PermconError, filetype::FileType, perm::FilePermission};
+use std::{fmt, str::FromStr};
+
+/// The permission new files are created with before the umask is applied.
+pub const DEFAULT_FILE_BITS: u16 = 0o666;
+
+/// The permission new directories are created with before the umask is applied.
+pub const DEFAULT_DIRECTORY_BITS: u16 = 0o777;
+
+/// Represents a parsed umask (e.g., `0027`), i.e., the permission bits that
+/// are removed from newly created files and directories.
+///
+/// ```rust
+/// use std::str::FromStr;
+/// use permcon::umask::Umask;
+///
+/// let umask = Umask::from_str("027").unwrap();
+///
+/// assert_eq!(umask, Umask::from_str("u=rwx,g=rx,o=").unwrap());
+/// assert_eq!(umask.file_permission().to_symbolic_str(), "-rw-r-----");
+/// assert_eq!(umask.directory_permission().to_symbolic_str(), "drwxr-x---");
+/// assert_eq!(umask.to_string(), "0027");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Umask {
+    pub bits: u16,
+}
+
+impl Umask {
+    /// Returns the permission a new regular file gets (`0666` minus the umask).
+    pub fn file_permission(&self) -> FilePermission {
+        FilePermission::from_bits(DEFAULT_FILE_BITS & !self.bits, FileType::Regular)
+    }
+
+    /// Returns the permission a new directory gets (`0777` minus the umask).
+    pub fn directory_permission(&self) -> FilePermission {
+        FilePermission::from_bits(DEFAULT_DIRECTORY_BITS & !self.bits, FileType::Directory)
+    }
+
+    /// Suggests the umask that creates files (or directories, if the file type
+    /// of `permission` is a directory) with the given permission.
+    ///
+    /// Files never get execute from their defaults, so for anything but a
+    /// directory the execute bit of a class is kept wherever read is granted.
+    /// That way directories created with the same umask stay traversable.
+    ///
+    /// ```rust
+    /// use permcon::{umask::Umask, FilePermission};
+    ///
+    /// let file = FilePermission::try_from("-rw-r-----").unwrap();
+    /// assert_eq!(Umask::suggest(&file).to_string(), "0027");
+    ///
+    /// let dir = FilePermission::try_from("drwxrwxr-x").unwrap();
+    /// assert_eq!(Umask::suggest(&dir).to_string(), "0002");
+    /// ```
+    pub fn suggest(permission: &FilePermission) -> Self {
+        let mut allowed = permission.to_bits() & DEFAULT_DIRECTORY_BITS;
+
+        if permission.filetype != FileType::Directory {
+            // copy every read bit (0o444) into the execute position (0o111)
+            allowed |= (allowed & 0o444) >> 2;
+        }
+
+        Umask {
+            bits: DEFAULT_DIRECTORY_BITS & !allowed,
+        }
+    }
+
+    /// Applies a symbolic umask expression like the shell's `umask g+w` does:
+    /// the expression edits the permissions the umask allows, not the masked
+    /// bits, so `g+w` clears the group write bit of the umask.
+    ///
+    /// ```rust
+    /// use std::str::FromStr;
+    /// use permcon::{umask::Umask, ChmodExpr};
+    ///
+    /// let umask = Umask::from_str("022").unwrap();
+    /// let expr = ChmodExpr::from_str("g+w").unwrap();
+    ///
+    /// assert_eq!(umask.apply_expr(&expr).to_string(), "0002");
+    /// ```
+    pub fn apply_expr(&self, expr: &ChmodExpr) -> Self {
+        let allowed = DEFAULT_DIRECTORY_BITS & !self.bits;
+        let allowed = expr.apply_to_bits(allowed, false) & DEFAULT_DIRECTORY_BITS;
+
+        Umask {
+            bits: DEFAULT_DIRECTORY_BITS & !allowed,
+        }
+    }
+
+    /// Serializes the umask the way `umask -S` prints it (e.g., `u=rwx,g=rx,o=`).
+    pub fn to_symbolic_str(&self) -> String {
+        let allowed = DEFAULT_DIRECTORY_BITS & !self.bits;
+
+        ["u", "g", "o"]
+            .iter()
+            .zip([6, 3, 0])
+            .map(|(class, shift)| {
+                let perms: String = ['r', 'w', 'x']
+                    .iter()
+                    .zip([0o4, 0o2, 0o1])
+                    .filter(|(_, bit)| (allowed >> shift) & bit != 0)
+                    .map(|(char, _)| char)
+                    .collect();
+
+                format!("{class}={perms}")
+            })
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+}
+
+impl FromStr for Umask {
+    type Err = PermconError;
+
+    /// Tries to parse an octal umask (`0022`, `022`) or the symbolic form
+    /// printed by `umask -S` (`u=rwx,g=rx,o=`).
+    fn from_str(umask: &str) -> Result<Self, Self::Err> {
+        let is_numeric = !umask.is_empty() && umask.chars().all(|ch| ch.is_ascii_digit());
+
+        if is_numeric {
+            if umask.len() > 4 {
+                return Err(PermconError::InvalidLength {
+                    input: umask.to_string(),
+                    expected: vec![1, 2, 3, 4],
+                    found: umask.len(),
+                });
+            }
+
+            if let Some((position, digit)) = umask.chars().enumerate().find(|(_, ch)| *ch > '7') {
+                return Err(PermconError::OctalDigitOutOfRange {
+                    input: umask.to_string(),
+                    position,
+                    digit: digit.to_digit(10).unwrap() as u8,
+                });
+            }
+
+            let bits = u16::from_str_radix(umask, 8).unwrap();
+
+            if bits > DEFAULT_DIRECTORY_BITS {
+                return Err(PermconError::UmaskOutOfRange {
+                    input: umask.to_string(),
+                });
+            }
+
+            return Ok(Umask { bits });
+        }
+
+        // Symbolic umasks describe the permissions that are kept, starting
+        // from an empty umask.
+        Ok(Umask { bits: 0 }.apply_expr(&ChmodExpr::from_str(umask)?))
+    }
+}
+
+impl fmt::Display for Umask {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04o}", self.bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_umask() {
+        let test_cases: [(&str, u16); 6] = [
+            ("0022", 0o022),
+            ("022", 0o022),
+            ("27", 0o027),
+            ("u=rwx,g=rx,o=", 0o027),
+            ("u=rwx,g=rwx,o=rx", 0o002),
+            ("go-w", 0o022),
+        ];
+
+        for (umask, bits) in test_cases {
+            assert_eq!(Umask::from_str(umask), Ok(Umask { bits }), "{umask}");
+        }
+
+        for umask in ["", "1022", "0o22", "089", "u=rwz"] {
+            assert!(Umask::from_str(umask).is_err(), "{umask}");
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_apply_expr_like_the_shell() {
+        // (umask, expression, `umask` after `umask <expression>` in sh)
+        let test_cases: [(&str, &str, &str); 5] = [
+            ("022", "g+w", "0002"),
+            ("027", "u-w,o+r", "0223"),
+            ("022", "g=r", "0032"),
+            ("077", "a+rx", "0022"),
+            ("000", "go-rwx", "0077"),
+        ];
+
+        for (umask, expr, expected) in test_cases {
+            let applied = Umask::from_str(umask)
+                .unwrap()
+                .apply_expr(&ChmodExpr::from_str(expr).unwrap());
+
+            assert_eq!(applied.to_string(), expected, "umask {umask}; umask {expr}");
+
+            let output = std::process::Command::new("sh")
+                .arg("-c")
+                .arg(format!("umask {umask}; umask {expr}; umask"))
+                .output()
+                .unwrap();
+
+            assert_eq!(
+                String::from_utf8_lossy(&output.stdout).trim(),
+                expected,
+                "umask {umask}; umask {expr}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_permissions() {
+        let test_cases: [(u16, &str, &str); 4] = [
+            (0o022, "0644", "0755"),
+            (0o027, "0640", "0750"),
+            (0o077, "0600", "0700"),
+            (0o002, "0664", "0775"),
+        ];
+
+        for (bits, file, directory) in test_cases {
+            let umask = Umask { bits };
+
+            assert_eq!(umask.file_permission().to_octal_str(), file);
+            assert_eq!(umask.directory_permission().to_octal_str(), directory);
+            assert_eq!(Umask::suggest(&umask.file_permission()), umask);
+            assert_eq!(Umask::suggest(&umask.directory_permission()), umask);
+            assert_eq!(Umask::from_str(&umask.to_symbolic_str()), Ok(umask));
+        }
+    }
+}
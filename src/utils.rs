@@ -1,3 +1,5 @@
+use crate::{error::PermconError, filetype::FileType};
+
 /// Parses an octal digit and turns it to a `[bool; 3]` based on its bits.
 /// **Note:** The number must be within `0..=7` range.
 ///
@@ -14,11 +16,13 @@
 /// // 4 -> 100 -> [true, false, false]
 /// assert_eq!(Ok([true, false, false]), parse_octal_digit(4u8));
 /// ```
-pub fn parse_octal_digit(digit: u8) -> Result<[bool; 3], String> {
+pub fn parse_octal_digit(digit: u8) -> Result<[bool; 3], PermconError> {
     if digit > 7 {
-        return Err(format!(
-            "The digit must be within 0..=7 range. Found {digit}!"
-        ));
+        return Err(PermconError::OctalDigitOutOfRange {
+            input: digit.to_string(),
+            position: 0,
+            digit,
+        });
     }
 
     let permission: [bool; 3] = format!("{:03b}", digit)
@@ -71,7 +75,7 @@ pub fn parse_symbolic_execution_bit(bit: char) -> (bool, bool) {
         return (true, false);
     }
 
-    return (false, false);
+    (false, false)
 }
 
 /// Returns the full file type from the symbolic file type char.
@@ -82,18 +86,7 @@ pub fn parse_symbolic_execution_bit(bit: char) -> (bool, bool) {
 /// assert_eq!(String::from("Directory"), get_filetype_from_char('d'))
 /// ```
 pub fn get_filetype_from_char(ft_char: char) -> String {
-    let file_type = match ft_char {
-        '-' => "Regular File",
-        'd' => "Directory",
-        'l' => "Symbolic Link",
-        'b' => "Block Device",
-        'c' => "Character Device",
-        's' => "Socket",
-        'p' => "Named Pipe",
-        _ => "Unknown",
-    };
-
-    file_type.to_string()
+    FileType::from_char(ft_char).to_string()
 }
 
 #[cfg(test)]
@@ -0,0 +1,364 @@
+use crate::{
+    filetype::FileType,
+    lint::{lint_archive_entry, Finding, Severity},
+    mode::Mode,
+    perm::FilePermission,
+};
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+const END_OF_CENTRAL_DIRECTORY: u32 = 0x0605_4b50;
+const ZIP64_END_OF_CENTRAL_DIRECTORY: u32 = 0x0606_4b50;
+const ZIP64_END_LOCATOR: u32 = 0x0706_4b50;
+const CENTRAL_FILE_HEADER: u32 = 0x0201_4b50;
+
+/// The size of the end of central directory record without the comment.
+const END_RECORD_SIZE: usize = 22;
+
+/// The "version made by" host systems whose external attributes hold a Unix
+/// mode: Unix and macOS.
+const UNIX_HOSTS: [u8; 2] = [3, 19];
+
+/// File extensions of scripts that are meant to be executed.
+const SCRIPT_EXTENSIONS: [&str; 8] = [
+    ".sh", ".bash", ".zsh", ".py", ".pl", ".rb", ".command", ".run",
+];
+
+/// Represents an entry of the central directory of a zip file.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ZipEntry {
+    pub path: String,
+    /// The Unix mode of the external file attributes, `None` if the archiver
+    /// recorded none (e.g., a zip made on Windows). Without a file type in
+    /// the mode, a path ending with `/` is a directory.
+    pub permission: Option<FilePermission>,
+    /// The uncompressed size.
+    pub size: u64,
+    /// The modification time as `YYYY-MM-DD HH:MM`.
+    pub modified: String,
+}
+
+impl ZipEntry {
+    /// Returns `true` if the entry is a directory, with or without a mode.
+    pub fn is_directory(&self) -> bool {
+        match &self.permission {
+            Some(permission) => permission.filetype == FileType::Directory,
+            None => self.path.ends_with('/'),
+        }
+    }
+}
+
+/// Reads the central directory of a zip file (including ZIP64) and decodes
+/// the Unix mode of every entry.
+///
+/// ```rust
+/// use permcon::zip::read_zip_entries;
+/// use permcon::FileType;
+///
+/// let archive = std::fs::File::open("tests/fixtures/release.zip").unwrap();
+/// let entries = read_zip_entries(archive).unwrap();
+///
+/// assert_eq!(entries[2].path, "release/bin/tool");
+/// assert_eq!(entries[2].permission.as_ref().unwrap().to_symbolic_str(), "-rwxr-xr-x");
+/// assert_eq!(entries[5].permission.as_ref().unwrap().filetype, FileType::Symlink);
+///
+/// // made on Windows
+/// assert_eq!(entries[6].path, "release/README.txt");
+/// assert_eq!(entries[6].permission, None);
+/// ```
+pub fn read_zip_entries<R: Read + Seek>(mut reader: R) -> io::Result<Vec<ZipEntry>> {
+    let (mut entry_count, mut directory_offset, end_offset) = read_end_record(&mut reader)?;
+
+    if entry_count == 0xffff || directory_offset == 0xffff_ffff {
+        (entry_count, directory_offset) = read_zip64_end_record(&mut reader, end_offset)?;
+    }
+
+    reader.seek(SeekFrom::Start(directory_offset))?;
+
+    let mut entries = Vec::new();
+
+    for _ in 0..entry_count {
+        entries.push(read_central_file_header(&mut reader)?);
+    }
+
+    Ok(entries)
+}
+
+/// Checks an entry for a missing Unix mode, a script without execute
+/// permission and everything [`lint_archive_entry`] reports (e.g., setuid
+/// bits).
+///
+/// ```rust
+/// use permcon::zip::{lint_zip_entry, read_zip_entries};
+///
+/// let archive = std::fs::File::open("tests/fixtures/release.zip").unwrap();
+/// let entries = read_zip_entries(archive).unwrap();
+///
+/// assert_eq!(entries[4].path, "release/install.sh");
+/// assert_eq!(lint_zip_entry(&entries[4])[0].id, "script-without-execute");
+/// ```
+pub fn lint_zip_entry(entry: &ZipEntry) -> Vec<Finding> {
+    let Some(permission) = &entry.permission else {
+        return vec![Finding {
+            id: "missing-unix-mode",
+            severity: Severity::Warning,
+            explanation: "the entry has no Unix mode, so it is extracted with the default permission and loses any execute bit",
+        }];
+    };
+
+    let mut findings = Vec::new();
+
+    if permission.filetype == FileType::Regular
+        && is_script(&entry.path)
+        && !permission.user.execute
+    {
+        findings.push(Finding {
+            id: "script-without-execute",
+            severity: Severity::Warning,
+            explanation: "the script is extracted without execute permission",
+        });
+    }
+
+    findings.extend(lint_archive_entry(permission, Path::new(&entry.path)));
+    findings
+}
+
+fn is_script(path: &str) -> bool {
+    let path = Path::new(path);
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+
+    SCRIPT_EXTENSIONS
+        .iter()
+        .any(|extension| name.ends_with(extension))
+        || path
+            .parent()
+            .is_some_and(|parent| parent.ends_with("bin") || parent.ends_with("sbin"))
+}
+
+/// Finds the end of central directory record, which is followed by a comment
+/// of up to 65535 bytes, and returns the entry count, the offset of the
+/// central directory and the offset of the record.
+fn read_end_record<R: Read + Seek>(reader: &mut R) -> io::Result<(u64, u64, u64)> {
+    let length = reader.seek(SeekFrom::End(0))?;
+    let tail_length = length.min((END_RECORD_SIZE + 0xffff) as u64);
+
+    reader.seek(SeekFrom::Start(length - tail_length))?;
+
+    let mut tail = vec![0u8; tail_length as usize];
+    reader.read_exact(&mut tail)?;
+
+    let position = (0..tail.len().saturating_sub(END_RECORD_SIZE - 1))
+        .rev()
+        .find(|position| u32_at(&tail, *position) == END_OF_CENTRAL_DIRECTORY)
+        .ok_or_else(|| invalid_data("the end of central directory record is missing"))?;
+
+    let record = &tail[position..];
+
+    Ok((
+        u64::from(u16_at(record, 10)),
+        u64::from(u32_at(record, 16)),
+        length - tail_length + position as u64,
+    ))
+}
+
+/// Reads the entry count and the central directory offset of a ZIP64
+/// archive from the record the locator before `end_offset` points to.
+fn read_zip64_end_record<R: Read + Seek>(
+    reader: &mut R,
+    end_offset: u64,
+) -> io::Result<(u64, u64)> {
+    let not_zip64 = || invalid_data("the ZIP64 end of central directory record is missing");
+
+    let locator_offset = end_offset.checked_sub(20).ok_or_else(not_zip64)?;
+    let mut locator = [0u8; 20];
+
+    reader.seek(SeekFrom::Start(locator_offset))?;
+    reader.read_exact(&mut locator)?;
+
+    if u32_at(&locator, 0) != ZIP64_END_LOCATOR {
+        return Err(not_zip64());
+    }
+
+    let mut record = [0u8; 56];
+
+    reader.seek(SeekFrom::Start(u64_at(&locator, 8)))?;
+    reader.read_exact(&mut record)?;
+
+    if u32_at(&record, 0) != ZIP64_END_OF_CENTRAL_DIRECTORY {
+        return Err(not_zip64());
+    }
+
+    Ok((u64_at(&record, 32), u64_at(&record, 48)))
+}
+
+fn read_central_file_header<R: Read>(reader: &mut R) -> io::Result<ZipEntry> {
+    let mut header = [0u8; 46];
+    reader.read_exact(&mut header)?;
+
+    if u32_at(&header, 0) != CENTRAL_FILE_HEADER {
+        return Err(invalid_data("a central directory file header is malformed"));
+    }
+
+    let host_system = header[5];
+    let time = u16_at(&header, 12);
+    let date = u16_at(&header, 14);
+    let mut size = u64::from(u32_at(&header, 24));
+    let mode = u32_at(&header, 38) >> 16;
+
+    let mut name = vec![0u8; usize::from(u16_at(&header, 28))];
+    let mut extra = vec![0u8; usize::from(u16_at(&header, 30))];
+    let mut comment = vec![0u8; usize::from(u16_at(&header, 32))];
+
+    reader.read_exact(&mut name)?;
+    reader.read_exact(&mut extra)?;
+    reader.read_exact(&mut comment)?;
+
+    if size == 0xffff_ffff {
+        size = zip64_size(&extra).unwrap_or(size);
+    }
+
+    let path = String::from_utf8_lossy(&name).into_owned();
+
+    let permission = (UNIX_HOSTS.contains(&host_system) && mode != 0).then(|| {
+        let filetype = match FileType::from_mode(mode) {
+            FileType::Unknown if path.ends_with('/') => FileType::Directory,
+            FileType::Unknown => FileType::Regular,
+            filetype => filetype,
+        };
+
+        FilePermission::from_bits(Mode::new(mode).permission_bits(), filetype)
+    });
+
+    Ok(ZipEntry {
+        path,
+        permission,
+        size,
+        modified: format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            1980 + (date >> 9),
+            (date >> 5) & 0xf,
+            date & 0x1f,
+            time >> 11,
+            (time >> 5) & 0x3f,
+        ),
+    })
+}
+
+/// Returns the uncompressed size of the ZIP64 extra field, which comes
+/// first in it.
+fn zip64_size(mut extra: &[u8]) -> Option<u64> {
+    while extra.len() >= 4 {
+        let (id, length) = (u16_at(extra, 0), usize::from(u16_at(extra, 2)));
+        let data = extra.get(4..4 + length)?;
+
+        if id == 0x0001 && data.len() >= 8 {
+            return Some(u64_at(data, 0));
+        }
+
+        extra = &extra[4 + length..];
+    }
+
+    None
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> u16 {
+    u16::from_le_bytes([bytes[offset], bytes[offset + 1]])
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid zip file: {message}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const FIXTURE: &[u8] = include_bytes!("../tests/fixtures/release.zip");
+
+    #[test]
+    fn test_read_fixture() {
+        // (path, symbolic, finding ids)
+        let expected: [(&str, Option<&str>, &[&str]); 8] = [
+            ("release/", Some("drwxr-xr-x"), &[]),
+            ("release/bin/", Some("drwxr-xr-x"), &[]),
+            ("release/bin/tool", Some("-rwxr-xr-x"), &[]),
+            ("release/bin/helper", Some("-rwsr-xr-x"), &["setid-file"]),
+            (
+                "release/install.sh",
+                Some("-rw-r--r--"),
+                &["script-without-execute"],
+            ),
+            ("release/latest", Some("lrwxrwxrwx"), &[]),
+            ("release/README.txt", None, &["missing-unix-mode"]),
+            // a mode without file type bits
+            ("release/data/", Some("drwxr-xr-x"), &[]),
+        ];
+
+        let entries = read_zip_entries(Cursor::new(FIXTURE)).unwrap();
+
+        assert_eq!(entries.len(), expected.len());
+
+        for (entry, (path, symbolic, ids)) in entries.iter().zip(expected) {
+            let findings: Vec<&str> = lint_zip_entry(entry)
+                .iter()
+                .map(|finding| finding.id)
+                .collect();
+
+            assert_eq!(entry.path, path);
+            assert_eq!(
+                entry
+                    .permission
+                    .as_ref()
+                    .map(FilePermission::to_symbolic_str)
+                    .as_deref(),
+                symbolic,
+                "{path}"
+            );
+            assert_eq!(findings, ids, "{path}");
+            assert_eq!(entry.modified, "2025-10-17 10:00");
+        }
+
+        assert_eq!(entries[2].size, 20);
+        assert!(entries[6].path.ends_with(".txt") && !entries[6].is_directory());
+        assert!(entries[7].is_directory());
+    }
+
+    #[test]
+    fn test_read_zip64() {
+        let fixture = include_bytes!("../tests/fixtures/zip64.zip");
+        let entries = read_zip_entries(Cursor::new(fixture)).unwrap();
+
+        let modes: Vec<String> = entries
+            .iter()
+            .map(|entry| entry.permission.as_ref().unwrap().to_octal_str())
+            .collect();
+
+        assert_eq!(modes, ["0755", "0644"]);
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        let truncated = &FIXTURE[..FIXTURE.len() - 10];
+
+        for file in [truncated, &FIXTURE[..100], &[][..]] {
+            assert!(read_zip_entries(Cursor::new(file)).is_err());
+        }
+    }
+}